@@ -0,0 +1,123 @@
+use cookie::Cookie;
+use criterion::{Criterion, criterion_group, criterion_main};
+use ri_cookie_header_string::{CookieHeaderStringExt, HeaderStringParser, ParseMode, name_value_pairs};
+use std::hint::black_box;
+
+/// A realistic, well-formed header with 10 cookies where every semicolon is a separator.
+const CLEAN_HEADER: &str = "session_id=8f14e45fceea167a5a36dedd4bea2543; csrftoken=Zk3pXq9LmN2vB7rT; \
+    theme=dark; lang=en-US; tz=Europe-Berlin; _ga=GA1.2.1234567890.1600000000; \
+    _gid=GA1.2.987654321.1600000000; consent=analytics-marketing; cart_items=3; last_visit=1700000000";
+
+/// A header with unquoted semicolons inside values that need the lookahead heuristics.
+const AMBIGUOUS_HEADER: &str = "session_id=abc;123;def; data=x=1;y=2; theme=dark; \
+    payload=\"quoted; value\"; note=a;b;c;d;e; lang=en-US";
+
+fn bench_parse(c: &mut Criterion) {
+    let strict = HeaderStringParser::builder().mode(ParseMode::Strict).build();
+
+    for (name, header) in [("clean_10", CLEAN_HEADER), ("ambiguous", AMBIGUOUS_HEADER)] {
+        let mut group = c.benchmark_group(name);
+
+        group.bench_function("lenient", |b| {
+            b.iter(|| Cookie::header_string_parse(black_box(header)).filter_map(|result| result.ok()).count())
+        });
+        group.bench_function("strict", |b| {
+            b.iter(|| strict.parse::<Cookie, _>(black_box(header)).filter_map(|result| result.ok()).count())
+        });
+        group.bench_function("pairs", |b| {
+            b.iter(|| name_value_pairs(black_box(header)).filter_map(|result| result.ok()).count())
+        });
+        group.bench_function("cookie_split", |b| {
+            b.iter(|| Cookie::split_parse(black_box(header)).filter_map(|result| result.ok()).count())
+        });
+
+        group.finish();
+    }
+}
+
+/// Headers made of long runs of semicolons, which used to cost time quadratic in their length.
+fn bench_semicolon_runs(c: &mut Criterion) {
+    let mut group = c.benchmark_group("semicolon_runs");
+
+    for len in [1_000, 10_000, 100_000] {
+        let empty_run = format!("a{}", ";".repeat(len));
+        let bare_tokens = "b;;".repeat(len / 3);
+
+        group.bench_function(format!("empty_run/{len}"), |b| {
+            b.iter(|| Cookie::header_string_parse(black_box(empty_run.as_str())).count())
+        });
+        group.bench_function(format!("bare_tokens/{len}"), |b| {
+            b.iter(|| Cookie::header_string_parse(black_box(bare_tokens.as_str())).count())
+        });
+    }
+
+    group.finish();
+}
+
+/// Picking one allowlisted cookie out of a header, where filtering by name first avoids
+/// constructing the other cookies.
+fn bench_filter(c: &mut Criterion) {
+    let mut group = c.benchmark_group("filter");
+
+    group.bench_function("parse_then_filter", |b| {
+        b.iter(|| {
+            Cookie::header_string_parse(black_box(CLEAN_HEADER))
+                .filter_map(|result| result.ok())
+                .filter(|cookie| cookie.name() == "session_id")
+                .count()
+        })
+    });
+    group.bench_function("filter_names", |b| {
+        b.iter(|| {
+            Cookie::header_string_parse_filter(black_box(CLEAN_HEADER), |name| name == "session_id")
+                .filter_map(|result| result.ok())
+                .count()
+        })
+    });
+
+    group.finish();
+}
+
+/// The same clean header as pure ASCII, which takes the byte-only fast path, and with a single
+/// non-ASCII character, which takes the UTF-8-aware path.
+fn bench_ascii(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ascii");
+    let non_ascii = CLEAN_HEADER.replace("Europe-Berlin", "Europe-Zürich");
+
+    for (name, header) in [("ascii", CLEAN_HEADER), ("non_ascii", non_ascii.as_str())] {
+        group.bench_function(format!("pairs/{name}"), |b| {
+            b.iter(|| name_value_pairs(black_box(header)).filter_map(|result| result.ok()).count())
+        });
+        group.bench_function(format!("lenient/{name}"), |b| {
+            b.iter(|| Cookie::header_string_parse(black_box(header)).filter_map(|result| result.ok()).count())
+        });
+    }
+
+    group.finish();
+}
+
+/// Summing the value lengths of a large header with the default `fold` compared to a loop over
+/// `next`.
+fn bench_fold(c: &mut Criterion) {
+    let header = [CLEAN_HEADER; 100].join("; ");
+    let value_len = |result: Result<Cookie, _>| result.map_or(0, |cookie| cookie.value().len());
+    let mut group = c.benchmark_group("fold");
+
+    group.bench_function("next_loop", |b| {
+        b.iter(|| {
+            let mut total = 0;
+            for result in Cookie::header_string_parse(black_box(header.as_str())) {
+                total += value_len(result);
+            }
+            total
+        })
+    });
+    group.bench_function("fold", |b| {
+        b.iter(|| Cookie::header_string_parse(black_box(header.as_str())).map(value_len).sum::<usize>())
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse, bench_semicolon_runs, bench_filter, bench_ascii, bench_fold);
+criterion_main!(benches);
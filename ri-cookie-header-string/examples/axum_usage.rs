@@ -0,0 +1,57 @@
+//! Example demonstrating cookie parsing in axum handlers through the `headers` crate.
+//!
+//! Run with: cargo run --example axum_usage --features headers
+
+#[cfg(feature = "headers")]
+fn main() {
+    use axum::Router;
+    use axum::extract::Request;
+    use axum::http::HeaderMap;
+    use axum::middleware::{self, Next};
+    use axum::response::Response;
+    use axum::routing::get;
+    use axum_extra::TypedHeader;
+    use axum_extra::headers::Cookie;
+    use http::header::COOKIE;
+    use ri_cookie_header_string::headers_support::typed_cookie;
+    use ri_cookie_header_string::http_support::canonical_header_value;
+
+    /// Read the cookies with this crate's heuristics instead of the `TypedHeader` extractor.
+    fn session(headers: &HeaderMap) -> String {
+        let cookie = typed_cookie(headers);
+        let session = cookie.as_ref().and_then(|cookie| cookie.get("session"));
+        format!("session: {session:?}")
+    }
+
+    async fn handler(headers: HeaderMap) -> String {
+        session(&headers)
+    }
+
+    /// Rewrite the `Cookie` header so that `TypedHeader<Cookie>` extractors further down read
+    /// the same cookies as this crate.
+    async fn canonical_cookies(mut request: Request, next: Next) -> Response {
+        if let Some(value) = request.headers().get(COOKIE) {
+            let value = canonical_header_value(value);
+            request.headers_mut().insert(COOKIE, value);
+        }
+        next.run(request).await
+    }
+
+    async fn typed_handler(TypedHeader(cookie): TypedHeader<Cookie>) -> String {
+        format!("session: {:?}", cookie.get("session"))
+    }
+
+    let _app: Router = Router::new()
+        .route("/", get(handler))
+        .route("/typed", get(typed_handler).layer(middleware::from_fn(canonical_cookies)));
+
+    // Serve `_app` with `axum::serve`; here the handler's logic is called directly
+    let mut headers = HeaderMap::new();
+    headers.insert(COOKIE, "session=abc;123; theme=dark".parse().unwrap());
+    println!("{}", session(&headers));
+}
+
+#[cfg(not(feature = "headers"))]
+fn main() {
+    println!("Please run with --features headers");
+}
@@ -4,7 +4,7 @@
 
 #[cfg(feature = "reqwest")]
 fn main() {
-    use ri_cookie_header_string::reqwest_support::parse_for_reqwest;
+    use ri_cookie_header_string::reqwest_support::{parse_for_reqwest, populate_jar};
 
     let cookie_header = "session=abc;xyz; user=john; token=abc123";
     let cookies: Vec<_> = parse_for_reqwest(cookie_header).filter_map(|result| result.ok()).collect();
@@ -13,6 +13,13 @@ fn main() {
     for cookie in &cookies {
         println!("  {}: {}", cookie.name(), cookie.value());
     }
+
+    // Populate a reqwest cookie jar in one call
+    let jar = reqwest::cookie::Jar::default();
+    let url: reqwest::Url = "https://example.com".parse().unwrap();
+    let (added, errors) = populate_jar(&jar, &url, "session=abc123; user=john");
+
+    println!("Added {} cookies to the jar ({} errors)", added, errors.len());
 }
 
 #[cfg(not(feature = "reqwest"))]
@@ -0,0 +1,41 @@
+#![no_main]
+
+use cookie::Cookie;
+use libfuzzer_sys::fuzz_target;
+use ri_cookie_header_string::{HeaderStringParser, ParseMode, Utf8Mode, name_value_pairs};
+
+fuzz_target!(|data: &[u8]| {
+    // Use the first byte to pick the parser options, and the rest as the header.
+    let Some((&options, header)) = data.split_first() else {
+        return;
+    };
+
+    let parser = HeaderStringParser::builder()
+        .mode(if options & 1 != 0 {
+            ParseMode::Strict
+        } else {
+            ParseMode::Lenient
+        })
+        .utf8_mode(if options & 2 != 0 {
+            Utf8Mode::Strict
+        } else {
+            Utf8Mode::Lossy
+        })
+        .allow_valueless(options & 4 != 0)
+        .trim_values(options & 8 != 0)
+        .separator(if options & 16 != 0 {
+            ','
+        } else {
+            ';'
+        })
+        .build();
+
+    parser.parse_bytes::<Cookie>(header).for_each(drop);
+
+    if let Ok(header) = std::str::from_utf8(header) {
+        for (range, _) in parser.parse::<Cookie, _>(header).indexed() {
+            assert!(header.is_char_boundary(range.start) && header.is_char_boundary(range.end));
+        }
+        name_value_pairs(header).for_each(drop);
+    }
+});
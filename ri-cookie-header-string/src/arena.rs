@@ -0,0 +1,129 @@
+//! Allocation of parsed names and values from a caller-provided arena.
+
+use crate::{CookieBuilder, HeaderParseError, HeaderStringCookies};
+
+/// Copies strings into storage that lives for `'c`, such as a bump arena reset per request.
+///
+/// Used by [`HeaderStringCookies::alloc_in`] for the names and values that can't be borrowed
+/// from the header. Implemented for every closure `Fn(&str) -> &'c str`, so with e.g.
+/// `bumpalo` an arena is plugged in as `|s| bump.alloc_str(s)`.
+pub trait StrAllocator<'c> {
+    /// Copy `s` into the allocator's storage.
+    fn alloc_str(&self, s: &str) -> &'c str;
+}
+
+impl<'c, F> StrAllocator<'c> for F
+where
+    F: Fn(&str) -> &'c str,
+{
+    fn alloc_str(&self, s: &str) -> &'c str {
+        self(s)
+    }
+}
+
+/// Iterator over cookies in a header string whose owned names and values are copied into a
+/// [`StrAllocator`] instead of `String`s.
+///
+/// Cookies that borrow from the header are built exactly as by [`HeaderStringCookies`]. When a
+/// name or value has to be owned, because it was decoded or normalized or because the header
+/// itself is owned, it is copied into the allocator and the cookie is built with
+/// [`CookieBuilder::new_borrowed`], so the cookie never holds a heap-allocated string. A
+/// [`ValueDecoder`](crate::ValueDecoder) may still allocate a temporary while decoding.
+///
+/// Created by [`HeaderStringCookies::alloc_in`].
+pub struct ArenaHeaderStringCookies<'c, C: CookieBuilder<'c>, A> {
+    inner: HeaderStringCookies<'c, C>,
+    allocator: A,
+}
+
+impl<'c, C: CookieBuilder<'c>> HeaderStringCookies<'c, C> {
+    /// Convert this iterator into one that copies the names and values it can't borrow from
+    /// the header into `allocator`.
+    ///
+    /// See [`ArenaHeaderStringCookies`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    /// use ri_cookie_header_string::CookieHeaderStringExt;
+    ///
+    /// // A stand-in for an arena, e.g. `|s| bump.alloc_str(s)` with `bumpalo`
+    /// let arena = |s: &str| -> &'static str { Box::leak(s.into()) };
+    /// let header = String::from("a=1; b=x;y");
+    /// let cookies: Vec<Cookie<'static>> = Cookie::header_string_parse(header)
+    ///     .alloc_in(arena)
+    ///     .filter_map(|result| result.ok())
+    ///     .collect();
+    ///
+    /// assert_eq!(cookies[1].value(), "x;y");
+    /// ```
+    pub fn alloc_in<A: StrAllocator<'c>>(self, allocator: A) -> ArenaHeaderStringCookies<'c, C, A> {
+        ArenaHeaderStringCookies {
+            inner: self,
+            allocator,
+        }
+    }
+}
+
+impl<'c, C: CookieBuilder<'c>, A: StrAllocator<'c>> Iterator for ArenaHeaderStringCookies<'c, C, A> {
+    type Item = Result<C, HeaderParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, ranges) = self.inner.next_ranges()?;
+        let allocator = &self.allocator;
+
+        let result = ranges.and_then(|(name, value)| {
+            let cookie = self.inner.build_cookie_with(name, value, |name, value| {
+                C::new_borrowed(allocator.alloc_str(&name), allocator.alloc_str(&value))
+            })?;
+            Ok(cookie)
+        });
+        if result.is_ok() {
+            self.inner.emitted += 1;
+        }
+        Some(result)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CookieHeaderStringExt, HeaderStringParser, ParseMode};
+    use alloc::boxed::Box;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+    use cookie::Cookie;
+    use core::cell::Cell;
+
+    #[test]
+    fn alloc_in_copies_owned_strings_into_the_allocator() {
+        let copies = Cell::new(0);
+        let arena = |s: &str| -> &'static str {
+            copies.set(copies.get() + 1);
+            Box::leak(s.into())
+        };
+
+        // A borrowed header needs no copies unless a name is normalized
+        let cookies: Vec<_> = Cookie::header_string_parse("a=1; b=x;y; flag").alloc_in(&arena).collect();
+        let expected: Vec<_> = Cookie::header_string_parse("a=1; b=x;y; flag").collect();
+        assert_eq!(cookies, expected);
+        assert_eq!(copies.get(), 0);
+
+        let parser = HeaderStringParser::builder().lowercase_names(true).build();
+        let cookie = parser.parse::<Cookie, _>("NAME=v").alloc_in(&arena).next().unwrap().unwrap();
+        assert_eq!(cookie.name_value(), ("name", "v"));
+        assert_eq!(copies.get(), 2);
+
+        // Every cookie of an owned header is copied, and errors are passed through
+        let parser = HeaderStringParser::builder().mode(ParseMode::Strict).error_on_empty_name(true).build();
+        let results: Vec<_> = parser.parse::<Cookie, _>(String::from("a=1; =x; b=2")).alloc_in(&arena).collect();
+        assert_eq!(results.len(), 3);
+        assert!(results[1].is_err());
+        assert_eq!(results[2].as_ref().map(Cookie::name_value), Ok(("b", "2")));
+        assert_eq!(copies.get(), 6);
+    }
+}
@@ -0,0 +1,190 @@
+//! Pluggable detection of cookie boundaries.
+
+use crate::{NameChars, is_token, skip_whitespace};
+use core::fmt;
+
+/// Decides whether a separator in a lenient parse ends the current cookie or is part of its
+/// value.
+///
+/// In [`ParseMode::Lenient`](crate::ParseMode::Lenient) the parser asks the strategy about each
+/// separator that follows a cookie value, skipping separators inside a double-quoted value.
+/// `bytes` is the whole header without a trailing line terminator and `semicolon_pos` is the
+/// index of the separator, which is `;` unless another separator is configured. Returning
+/// `false` keeps the separator in the value and moves on to the next one; the end of the header
+/// always ends a cookie.
+///
+/// Any `Fn(&[u8], usize) -> bool` closure is a strategy, which is convenient for domain-specific
+/// formats:
+///
+/// ```
+/// use cookie::Cookie;
+/// use ri_cookie_header_string::{BoundaryStrategy, DefaultStrategy, HeaderStringParser};
+///
+/// // Values of `list` are `;`-separated lists of numbers
+/// let parser = HeaderStringParser::builder()
+///     .boundary_strategy(|bytes: &[u8], pos: usize| {
+///         bytes.get(pos + 1).is_none_or(|b| !b.is_ascii_digit()) && DefaultStrategy::new().is_separator(bytes, pos)
+///     })
+///     .build();
+/// let cookies: Vec<Cookie> = parser.parse("list=1;2;3; a=b").filter_map(|result| result.ok()).collect();
+///
+/// assert_eq!(cookies[0].value(), "1;2;3");
+/// assert_eq!(cookies[1].value(), "b");
+/// ```
+pub trait BoundaryStrategy: Send + Sync {
+    /// Return whether the separator at `semicolon_pos` ends the current cookie.
+    fn is_separator(&self, bytes: &[u8], semicolon_pos: usize) -> bool;
+}
+
+impl fmt::Debug for dyn BoundaryStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("BoundaryStrategy")
+    }
+}
+
+impl<F> BoundaryStrategy for F
+where
+    F: Fn(&[u8], usize) -> bool + Send + Sync,
+{
+    fn is_separator(&self, bytes: &[u8], semicolon_pos: usize) -> bool {
+        self(bytes, semicolon_pos)
+    }
+}
+
+/// The built-in lookahead heuristic.
+///
+/// A separator ends the current cookie when it is followed, after optional whitespace, by the
+/// end of the header, another separator, or a name followed by `=`. Names are made of the
+/// [`name_chars`] set, ASCII letters, digits, `_` and `-` by default. With [`allow_valueless`]
+/// a bare token also starts a new cookie, and with [`legacy_attributes`] other than
+/// `AsCookies` so does a `$`-prefixed name. Anything else is treated as part of the value.
+///
+/// This is the strategy used when none is configured, matching the parser's other options.
+/// [`DefaultStrategy::new`] creates one for the default configuration, for use by custom
+/// strategies that only override some decisions.
+///
+/// [`allow_valueless`]: crate::HeaderStringParserBuilder::allow_valueless
+/// [`legacy_attributes`]: crate::HeaderStringParserBuilder::legacy_attributes
+/// [`name_chars`]: crate::HeaderStringParserBuilder::name_chars
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DefaultStrategy {
+    pub(crate) separator: u8,
+    pub(crate) allow_valueless: bool,
+    pub(crate) legacy_names: bool,
+    pub(crate) name_chars: NameChars,
+}
+
+impl DefaultStrategy {
+    /// Create the heuristic for the default parser configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Default for DefaultStrategy {
+    fn default() -> Self {
+        DefaultStrategy {
+            separator: b';',
+            allow_valueless: false,
+            legacy_names: false,
+            name_chars: NameChars::Default,
+        }
+    }
+}
+
+impl BoundaryStrategy for DefaultStrategy {
+    fn is_separator(&self, bytes: &[u8], semicolon_pos: usize) -> bool {
+        let sep = self.separator;
+        let start = skip_whitespace(bytes, semicolon_pos + 1, sep);
+        // A name never spans a separator, so nothing past the next one is looked at
+        let end = bytes[start..].iter().position(|&b| b == sep).map_or(bytes.len(), |k| start + k);
+
+        let Some(&first) = bytes.get(start) else {
+            return true;
+        };
+        if first == sep || (self.allow_valueless && str_at(bytes, start..end).is_some_and(|s| is_token(s.trim()))) {
+            return true;
+        }
+        if !(self.name_chars.allows(first) || (self.legacy_names && first == b'$')) {
+            return false;
+        }
+
+        let Some(eq_pos) = bytes[start..end].iter().position(|&b| b == b'=') else {
+            return false;
+        };
+        let Some(name) = str_at(bytes, start..start + eq_pos).map(str::trim) else {
+            return false;
+        };
+        let name = if self.legacy_names {
+            name.strip_prefix('$').unwrap_or(name)
+        } else {
+            name
+        };
+
+        // Compare bytes rather than chars so non-ASCII names never pass as ASCII
+        !name.is_empty() && name.bytes().all(|b| self.name_chars.allows(b))
+    }
+}
+
+/// Return the bytes in `range` as a string, or `None` if they are not valid UTF-8.
+fn str_at(bytes: &[u8], range: core::ops::Range<usize>) -> Option<&str> {
+    core::str::from_utf8(&bytes[range]).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HeaderStringParser, ParseMode};
+    use cookie::Cookie;
+
+    #[test]
+    fn default_strategy_decisions() {
+        let strategy = DefaultStrategy::new();
+        let cases = [
+            ("a=1; b=2", true),
+            ("a=1;b=2", true),
+            ("a=1;  b =2", true),
+            ("a=1;", true),
+            ("a=1; ;", true),
+            ("a=1;\u{a0}b=2", true),
+            ("a=1;2", false),
+            ("a=1; b", false),
+            ("a=1; =2", false),
+            ("a=1; $b=2", false),
+            ("a=1; b c=2", false),
+            ("a=1; é=2", false),
+            ("a=1; b; c=2", false),
+        ];
+
+        for (header, expected) in cases {
+            assert_eq!(strategy.is_separator(header.as_bytes(), 3), expected, "header: {header:?}");
+        }
+
+        let strategy = DefaultStrategy {
+            allow_valueless: true,
+            legacy_names: true,
+            ..strategy
+        };
+        assert!(strategy.is_separator(b"a=1; b", 3));
+        assert!(strategy.is_separator(b"a=1; $b=2", 3));
+        assert!(!strategy.is_separator(b"a=1; $=2", 3));
+        assert!(!strategy.is_separator(b"a=1; \xff=2", 3));
+    }
+
+    #[test]
+    fn custom_boundary_strategy() {
+        // Only a semicolon followed by a space separates cookies
+        let parser = HeaderStringParser::builder()
+            .boundary_strategy(|bytes: &[u8], pos: usize| bytes.get(pos + 1).is_none_or(|&b| b == b' '))
+            .build();
+        let cookies: Vec<Cookie> = parser.parse("a=1;b=2; c=\"x; y\"; d=4;").flatten().collect();
+        let actual: Vec<_> = cookies.iter().map(|c| c.name_value()).collect();
+
+        assert_eq!(actual, [("a", "1;b=2"), ("c", "x; y"), ("d", "4")]);
+
+        // Strict mode splits at every semicolon without asking the strategy
+        let parser =
+            HeaderStringParser::builder().mode(ParseMode::Strict).boundary_strategy(|_: &[u8], _| false).build();
+        assert_eq!(parser.parse::<Cookie, _>("a=1;b=2").flatten().count(), 2);
+    }
+}
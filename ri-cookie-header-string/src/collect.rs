@@ -0,0 +1,532 @@
+//! Eager collection helpers built on top of [`HeaderStringCookies`].
+
+use crate::{CookieBuilder, CookieHeaderStringExt, HeaderParseError, HeaderStringCookies, NameValue};
+use alloc::collections::BTreeMap;
+use alloc::collections::btree_map;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use cookie::{Cookie, CookieJar};
+use core::str::FromStr;
+#[cfg(feature = "indexmap")]
+use indexmap::IndexMap;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::collections::hash_map::Entry;
+
+/// Which cookie wins when the same name appears more than once in a header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicatePolicy {
+    /// Keep the value of the first cookie with a given name.
+    FirstWins,
+    /// Keep the value of the last cookie with a given name. This is the default and
+    /// matches how browsers resolve duplicates.
+    #[default]
+    LastWins,
+}
+
+/// How cookie names are compared when sorting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NameOrder {
+    /// Compare names byte by byte, so uppercase ASCII letters sort before lowercase ones. This
+    /// is the default.
+    #[default]
+    Ordinal,
+    /// Compare names ignoring ASCII case, so `b` sorts between `A` and `C`.
+    CaseInsensitive,
+}
+
+/// An owned, ordered collection of parsed cookie name/value pairs.
+///
+/// With the `serde` feature enabled this serializes as a list of `[name, value]` pairs,
+/// which makes it convenient for storing parsed cookie sets as JSON.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+pub struct ParsedCookies(pub Vec<(String, String)>);
+
+impl ParsedCookies {
+    /// Reconstruct a `Cookie` header string from the pairs, joined with `; `.
+    ///
+    /// See [`to_header_string`](crate::to_header_string) for how values are encoded.
+    pub fn to_header_string(&self) -> String {
+        crate::to_header_string(&self.0)
+    }
+}
+
+/// Parse a cookie header string with the default, lenient configuration.
+///
+/// Malformed segments that the lenient parser skips are left out, and any error it still
+/// reports, such as a value that fails to decode, fails the whole parse with the first such
+/// error. To keep the errors instead, parse into a [`CookieHeader`](crate::CookieHeader). To use
+/// another configuration, or to keep going past errors, collect the cookies of
+/// [`HeaderStringParser::parse`](crate::HeaderStringParser::parse) with
+/// [`collect_parsed`](HeaderStringCookies::collect_parsed) or
+/// [`partition_results`](HeaderStringCookies::partition_results) instead.
+///
+/// # Example
+///
+/// ```
+/// use ri_cookie_header_string::{HeaderParseError, ParsedCookies};
+///
+/// # fn main() -> Result<(), HeaderParseError> {
+/// let cookies: ParsedCookies = "session=abc;123; theme=dark".parse()?;
+///
+/// assert_eq!(cookies.0, [("session".to_string(), "abc;123".to_string()), ("theme".into(), "dark".into())]);
+/// # Ok(())
+/// # }
+/// ```
+impl FromStr for ParsedCookies {
+    type Err = HeaderParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Cookie::header_string_parse(s)
+            .map(|result| result.map(|cookie| (cookie.name().to_string(), cookie.value().to_string())))
+            .collect::<Result<_, _>>()
+            .map(ParsedCookies)
+    }
+}
+
+impl<'c, C: CookieBuilder<'c>> HeaderStringCookies<'c, C> {
+    /// Consume the iterator, separating parsed cookies from errors in a single pass.
+    ///
+    /// Unlike `filter_map(Result::ok)`, no failure is silently dropped, which makes it easy to
+    /// inspect every malformed entry of an untrusted header.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    /// use ri_cookie_header_string::HeaderStringParser;
+    ///
+    /// let parser = HeaderStringParser::builder().max_value_len(3).build();
+    /// let (cookies, errors) = parser.parse::<Cookie, _>("a=1; b=1234; c=3").partition_results();
+    ///
+    /// assert_eq!(cookies.len(), 2);
+    /// assert_eq!(errors.len(), 1);
+    ///
+    /// // Turn the pair into a `Result` when either all cookies or all errors are wanted
+    /// let result = if errors.is_empty() { Ok(cookies) } else { Err(errors) };
+    /// assert!(result.is_err());
+    /// ```
+    pub fn partition_results(self) -> (Vec<C>, Vec<HeaderParseError>) {
+        let mut cookies = Vec::new();
+        let mut errors = Vec::new();
+
+        for result in self {
+            match result {
+                Ok(cookie) => cookies.push(cookie),
+                Err(err) => errors.push(err),
+            }
+        }
+
+        (cookies, errors)
+    }
+}
+
+impl<'c, C: CookieBuilder<'c> + NameValue> HeaderStringCookies<'c, C> {
+    /// Collect the successfully parsed cookies into an owned [`ParsedCookies`], preserving order.
+    ///
+    /// Cookies that fail to parse are skipped.
+    pub fn collect_parsed(self) -> ParsedCookies {
+        ParsedCookies(
+            self.filter_map(|result| result.ok())
+                .map(|cookie| {
+                    let (name, value) = cookie.name_value();
+                    (name.to_string(), value.to_string())
+                })
+                .collect(),
+        )
+    }
+
+    /// Drop cookies whose name appeared earlier, preserving the order of the surviving cookies.
+    ///
+    /// Cookies that fail to parse are skipped. Names are compared like in [`collect_map`].
+    ///
+    /// [`collect_map`]: Self::collect_map
+    pub fn dedup_first_wins(self) -> Vec<(String, String)> {
+        self.dedup(DuplicatePolicy::FirstWins)
+    }
+
+    /// Drop cookies whose name appears again later, preserving the order of the surviving
+    /// cookies.
+    ///
+    /// Unlike [`collect_map`], the result is ordered, which makes it suitable for forwarding a
+    /// cleaned header to servers that care about cookie order. Cookies that fail to parse are
+    /// skipped. Names are compared like in [`collect_map`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    /// use ri_cookie_header_string::CookieHeaderStringExt;
+    ///
+    /// let pairs = Cookie::header_string_parse("a=1; b=2; a=3; c=4").dedup_last_wins();
+    ///
+    /// assert_eq!(pairs, [("b".to_string(), "2".to_string()), ("a".into(), "3".into()), ("c".into(), "4".into())]);
+    /// ```
+    ///
+    /// [`collect_map`]: Self::collect_map
+    pub fn dedup_last_wins(self) -> Vec<(String, String)> {
+        self.dedup(DuplicatePolicy::LastWins)
+    }
+
+    /// Collect the successfully parsed cookies, resolving duplicate names last-wins like a
+    /// browser would.
+    ///
+    /// The surviving cookies keep their relative order. Resolving duplicates requires seeing
+    /// the whole header, so this is eager and allocates a `Vec` of all cookies; use
+    /// [`dedup_last_wins`](Self::dedup_last_wins) for owned name/value pairs instead. Cookies
+    /// that fail to parse are skipped. Names are compared like in [`collect_map`].
+    ///
+    /// [`collect_map`]: Self::collect_map
+    pub fn collect_last_wins(self) -> Vec<C> {
+        self.dedup_cookies(DuplicatePolicy::LastWins)
+    }
+
+    /// Collect the successfully parsed cookies sorted by name.
+    ///
+    /// The sort is stable, so cookies with the same name, or with names that are equal under
+    /// `order`, keep their order in the header. Cookies that fail to parse are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    /// use ri_cookie_header_string::{CookieHeaderStringExt, NameOrder};
+    ///
+    /// let cookies = Cookie::header_string_parse("b=1; a=2; B=3").collect_sorted(NameOrder::Ordinal);
+    /// let names: Vec<_> = cookies.iter().map(|c| c.name()).collect();
+    ///
+    /// assert_eq!(names, ["B", "a", "b"]);
+    /// ```
+    pub fn collect_sorted(self, order: NameOrder) -> Vec<C> {
+        let mut cookies: Vec<C> = self.filter_map(|result| result.ok()).collect();
+        match order {
+            NameOrder::Ordinal => cookies.sort_by(|a, b| a.name_value().0.cmp(b.name_value().0)),
+            NameOrder::CaseInsensitive => cookies.sort_by(|a, b| {
+                let a = a.name_value().0.bytes().map(|b| b.to_ascii_lowercase());
+                a.cmp(b.name_value().0.bytes().map(|b| b.to_ascii_lowercase()))
+            }),
+        }
+        cookies
+    }
+
+    fn dedup(self, policy: DuplicatePolicy) -> Vec<(String, String)> {
+        self.dedup_cookies(policy)
+            .iter()
+            .map(|cookie| {
+                let (name, value) = cookie.name_value();
+                (name.to_string(), value.to_string())
+            })
+            .collect()
+    }
+
+    fn dedup_cookies(self, policy: DuplicatePolicy) -> Vec<C> {
+        let case_insensitive = self.parser.case_insensitive_names;
+        let cookies: Vec<C> = self.filter_map(|result| result.ok()).collect();
+
+        // Maps each (possibly case-folded) name to the index of the cookie that wins
+        let mut winners = BTreeMap::new();
+        for (index, cookie) in cookies.iter().enumerate() {
+            let name = cookie.name_value().0;
+            let key = if case_insensitive {
+                name.to_ascii_lowercase()
+            } else {
+                name.to_string()
+            };
+            match winners.entry(key) {
+                btree_map::Entry::Occupied(mut entry) => {
+                    if policy == DuplicatePolicy::LastWins {
+                        entry.insert(index);
+                    }
+                }
+                btree_map::Entry::Vacant(entry) => {
+                    entry.insert(index);
+                }
+            }
+        }
+
+        let mut keep = alloc::vec![false; cookies.len()];
+        for index in winners.into_values() {
+            keep[index] = true;
+        }
+
+        cookies.into_iter().zip(keep).filter_map(|(cookie, keep)| keep.then_some(cookie)).collect()
+    }
+
+    /// Collect the successfully parsed cookies into a map keyed by cookie name.
+    ///
+    /// Cookies that fail to parse are skipped. Duplicate names are resolved according to `policy`.
+    ///
+    /// If the parser was built with [`case_insensitive_names`], names that differ only in ASCII
+    /// case are treated as duplicates and the first-seen casing is used as the key.
+    ///
+    /// [`case_insensitive_names`]: crate::HeaderStringParserBuilder::case_insensitive_names
+    #[cfg(feature = "std")]
+    pub fn collect_map(self, policy: DuplicatePolicy) -> HashMap<String, String> {
+        let mut map = HashMap::new();
+        // Maps the ASCII-lowercased name to the first-seen casing when comparing case-insensitively
+        let mut first_seen: Option<HashMap<String, String>> = self.parser.case_insensitive_names.then(HashMap::new);
+
+        for cookie in self.filter_map(|result| result.ok()) {
+            let (name, value) = cookie.name_value();
+            let key = match first_seen.as_mut() {
+                Some(first_seen) => {
+                    first_seen.entry(name.to_ascii_lowercase()).or_insert_with(|| name.to_string()).clone()
+                }
+                None => name.to_string(),
+            };
+
+            match map.entry(key) {
+                Entry::Occupied(mut entry) => {
+                    if policy == DuplicatePolicy::LastWins {
+                        entry.insert(value.to_string());
+                    }
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(value.to_string());
+                }
+            }
+        }
+
+        map
+    }
+
+    /// Collect the successfully parsed cookies into an [`IndexMap`] keyed by cookie name,
+    /// preserving the order of the header.
+    ///
+    /// Each name keeps the position of its first occurrence; with
+    /// [`DuplicatePolicy::LastWins`] a later duplicate replaces the value without moving the
+    /// entry. Use [`dedup_last_wins`](Self::dedup_last_wins) to order names by their last
+    /// occurrence instead. Cookies that fail to parse are skipped, and names are compared like
+    /// in [`collect_map`](Self::collect_map).
+    #[cfg(feature = "indexmap")]
+    pub fn collect_indexmap(self, policy: DuplicatePolicy) -> IndexMap<String, String> {
+        let mut map = IndexMap::new();
+        // Maps the ASCII-lowercased name to the first-seen casing when comparing case-insensitively
+        let mut first_seen: Option<HashMap<String, String>> = self.parser.case_insensitive_names.then(HashMap::new);
+
+        for cookie in self.filter_map(|result| result.ok()) {
+            let (name, value) = cookie.name_value();
+            let key = match first_seen.as_mut() {
+                Some(first_seen) => {
+                    first_seen.entry(name.to_ascii_lowercase()).or_insert_with(|| name.to_string()).clone()
+                }
+                None => name.to_string(),
+            };
+
+            match policy {
+                DuplicatePolicy::LastWins => {
+                    map.insert(key, value.to_string());
+                }
+                DuplicatePolicy::FirstWins => {
+                    map.entry(key).or_insert_with(|| value.to_string());
+                }
+            }
+        }
+
+        map
+    }
+}
+
+impl<'c> HeaderStringCookies<'c, Cookie<'c>> {
+    /// Consume the iterator, adding every parsed cookie to a new [`CookieJar`] and collecting
+    /// the errors.
+    ///
+    /// Cookies are added with [`CookieJar::add_original`], since cookies received in a request
+    /// header are the jar's starting state rather than changes to send back; the jar's
+    /// [`delta`](CookieJar::delta) is therefore empty. A cookie with a duplicate name replaces
+    /// the earlier one, so duplicates resolve last-wins like in a browser.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    /// use ri_cookie_header_string::CookieHeaderStringExt;
+    ///
+    /// let (jar, errors) = Cookie::header_string_parse("session=abc;123; theme=dark").into_jar();
+    ///
+    /// assert_eq!(jar.get("session").unwrap().value(), "abc;123");
+    /// assert!(errors.is_empty());
+    /// ```
+    pub fn into_jar(self) -> (CookieJar, Vec<HeaderParseError>) {
+        let mut jar = CookieJar::new();
+        let mut errors = Vec::new();
+
+        for result in self {
+            match result {
+                Ok(cookie) => jar.add_original(cookie.into_owned()),
+                Err(err) => errors.push(err),
+            }
+        }
+
+        (jar, errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CookieHeaderStringExt, HeaderStringParser, ParseMode};
+
+    #[test]
+    fn collect_map_duplicate_policy() {
+        let cookie_header = "a=1; b=2; a=3";
+
+        let last = Cookie::header_string_parse_map(cookie_header, DuplicatePolicy::LastWins);
+        assert_eq!(last.len(), 2);
+        assert_eq!(last["a"], "3");
+        assert_eq!(last["b"], "2");
+
+        let first = Cookie::header_string_parse_map(cookie_header, DuplicatePolicy::FirstWins);
+        assert_eq!(first["a"], "1");
+    }
+
+    #[test]
+    #[cfg(feature = "indexmap")]
+    fn collect_indexmap_preserves_order() {
+        let cookie_header = "c=1; a=2; b=x;y; a=4; c=5; d=6";
+
+        let last = Cookie::header_string_parse_indexmap(cookie_header, DuplicatePolicy::LastWins);
+        let entries: Vec<_> = last.iter().map(|(name, value)| (name.as_str(), value.as_str())).collect();
+        assert_eq!(entries, [("c", "5"), ("a", "4"), ("b", "x;y"), ("d", "6")]);
+
+        let first = Cookie::header_string_parse_indexmap(cookie_header, DuplicatePolicy::FirstWins);
+        let entries: Vec<_> = first.iter().map(|(name, value)| (name.as_str(), value.as_str())).collect();
+        assert_eq!(entries, [("c", "1"), ("a", "2"), ("b", "x;y"), ("d", "6")]);
+
+        let parser = HeaderStringParser::builder().case_insensitive_names(true).build();
+        let map = parser.parse::<Cookie, _>("Sid=1; x=2; SID=3").collect_indexmap(DuplicatePolicy::LastWins);
+        assert_eq!(map.keys().collect::<Vec<_>>(), ["Sid", "x"]);
+        assert_eq!(map["Sid"], "3");
+    }
+
+    #[test]
+    fn collect_sorted_name_orders() {
+        let cookie_header = "b=1; a=2; B=3; _x=4; a=5; A=6";
+
+        let sorted = Cookie::header_string_parse_sorted(cookie_header, NameOrder::Ordinal);
+        let actual: Vec<_> = sorted.iter().map(Cookie::name_value).collect();
+        assert_eq!(actual, [("A", "6"), ("B", "3"), ("_x", "4"), ("a", "2"), ("a", "5"), ("b", "1")]);
+
+        // Names equal ignoring case keep their order in the header
+        let sorted = Cookie::header_string_parse_sorted(cookie_header, NameOrder::CaseInsensitive);
+        let actual: Vec<_> = sorted.iter().map(Cookie::name_value).collect();
+        assert_eq!(actual, [("_x", "4"), ("a", "2"), ("a", "5"), ("A", "6"), ("b", "1"), ("B", "3")]);
+    }
+
+    #[test]
+    fn parsed_cookies_from_str() {
+        let cookies: ParsedCookies = " a=1; ; b=x;y; =z".parse().unwrap();
+        assert_eq!(cookies, Cookie::header_string_parse_collect(" a=1; ; b=x;y; =z"));
+        assert_eq!(cookies.0, [("a".to_string(), "1".to_string()), ("b".into(), "x;y; =z".into())]);
+
+        assert_eq!("".parse::<ParsedCookies>(), Ok(ParsedCookies::default()));
+        #[cfg(feature = "percent-encode")]
+        assert!(matches!("a=1; b=%FF".parse::<ParsedCookies>(), Err(HeaderParseError::DecodeFailed { .. })));
+    }
+
+    #[test]
+    fn into_jar_collects_cookies_and_errors() {
+        let parser = HeaderStringParser::builder().mode(ParseMode::Strict).build();
+        let (jar, errors) = parser.parse::<Cookie, _>("a=1; flag; b=2; a=3").into_jar();
+
+        let mut actual: Vec<_> = jar.iter().map(|c| (c.name().to_string(), c.value().to_string())).collect();
+        actual.sort();
+        assert_eq!(actual, [("a".to_string(), "3".to_string()), ("b".into(), "2".into())]);
+        assert_eq!(jar.delta().count(), 0);
+        assert_eq!(errors, [cookie::ParseError::MissingPair.into()]);
+    }
+
+    #[test]
+    fn collect_map_case_insensitive_names() {
+        let cookie_header = "SessionId=1; sessionid=2; SESSIONID=3; other=x";
+
+        let map = Cookie::header_string_parse_map(cookie_header, DuplicatePolicy::LastWins);
+        assert_eq!(map.len(), 4);
+
+        let parser = HeaderStringParser::builder().case_insensitive_names(true).build();
+        let map = parser.parse::<Cookie, _>(cookie_header).collect_map(DuplicatePolicy::LastWins);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["SessionId"], "3");
+
+        let map = parser.parse::<Cookie, _>(cookie_header).collect_map(DuplicatePolicy::FirstWins);
+        assert_eq!(map["SessionId"], "1");
+
+        // The plain iterator is unaffected
+        assert_eq!(parser.parse::<Cookie, _>(cookie_header).count(), 4);
+    }
+
+    #[test]
+    fn dedup_preserves_order() {
+        let cookie_header = "a=1; b=2; A=3; a=4; c=5";
+        let pairs = |pairs: Vec<(String, String)>| -> Vec<String> {
+            pairs.into_iter().map(|(name, value)| format!("{name}={value}")).collect()
+        };
+
+        assert_eq!(pairs(Cookie::header_string_parse(cookie_header).dedup_last_wins()), ["b=2", "A=3", "a=4", "c=5"]);
+        assert_eq!(pairs(Cookie::header_string_parse(cookie_header).dedup_first_wins()), ["a=1", "b=2", "A=3", "c=5"]);
+
+        let parser = HeaderStringParser::builder().case_insensitive_names(true).build();
+        assert_eq!(pairs(parser.parse::<Cookie, _>(cookie_header).dedup_last_wins()), ["b=2", "a=4", "c=5"]);
+        assert_eq!(pairs(parser.parse::<Cookie, _>(cookie_header).dedup_first_wins()), ["a=1", "b=2", "c=5"]);
+    }
+
+    #[test]
+    fn collect_last_wins_cookies() {
+        let cookies = Cookie::header_string_parse("a=1; b=2; a=x;y; c=3").collect_last_wins();
+        let actual: Vec<_> = cookies.iter().map(|c| c.name_value()).collect();
+
+        assert_eq!(actual, [("b", "2"), ("a", "x;y"), ("c", "3")]);
+    }
+
+    #[test]
+    fn partition_results_keeps_every_failure() {
+        let parser = HeaderStringParser::builder().mode(crate::ParseMode::Strict).build();
+        let (cookies, errors) = parser.parse::<Cookie, _>("a=1; bad; =x; b=2").partition_results();
+
+        let names: Vec<_> = cookies.iter().map(|c| c.name()).collect();
+        assert_eq!(names, ["a", "b"]);
+        assert_eq!(
+            errors,
+            [
+                HeaderParseError::Cookie(cookie::ParseError::MissingPair),
+                HeaderParseError::Cookie(cookie::ParseError::EmptyName)
+            ]
+        );
+    }
+
+    #[test]
+    fn collect_parsed_round_trip() {
+        let parsed = Cookie::header_string_parse_collect("a=1;  session=abc;123 ;b=");
+
+        assert_eq!(parsed.0.len(), 3);
+        assert_eq!(parsed.to_header_string(), "a=1; session=abc%3B123; b=");
+        let reparsed = Cookie::header_string_parse_collect(parsed.to_header_string());
+        // Without a decoder the escaped semicolon is read back as written
+        #[cfg(not(feature = "percent-encode"))]
+        assert_eq!(reparsed.0[1], ("session".to_string(), "abc%3B123".to_string()));
+        #[cfg(feature = "percent-encode")]
+        assert_eq!(reparsed, parsed);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn collect_parsed_serde() {
+        let parsed = Cookie::header_string_parse_collect("a=1; b=x;y");
+        let json = serde_json::to_string(&parsed).unwrap();
+
+        assert_eq!(json, r#"[["a","1"],["b","x;y"]]"#);
+        assert_eq!(serde_json::from_str::<ParsedCookies>(&json).unwrap(), parsed);
+    }
+
+    #[test]
+    fn collect_map_keeps_semicolon_values() {
+        let map = Cookie::header_string_parse(";session=abc;123; other=value").collect_map(DuplicatePolicy::default());
+
+        assert_eq!(map["session"], "abc;123");
+        assert_eq!(map["other"], "value");
+    }
+}
@@ -0,0 +1,208 @@
+//! Pluggable decoding of cookie values.
+
+use alloc::borrow::Cow;
+use alloc::string::String;
+#[cfg(feature = "percent-encode")]
+use alloc::vec::Vec;
+use cookie::ParseError;
+use core::fmt;
+
+/// Decodes raw cookie values before cookies are constructed.
+///
+/// The parser calls the decoder on every value. Returning [`Cow::Borrowed`] for values that
+/// need no decoding lets the parser keep borrowing from the header string instead of
+/// allocating.
+///
+/// Any `Fn(&str) -> Result<String, ParseError>` closure is a decoder, which is convenient for
+/// legacy encodings such as base64:
+///
+/// ```
+/// use cookie::Cookie;
+/// use ri_cookie_header_string::HeaderStringParser;
+///
+/// let parser = HeaderStringParser::builder().decoder(|value: &str| Ok(value.chars().rev().collect())).build();
+/// let cookies: Vec<Cookie> = parser.parse("a=olleh").filter_map(|result| result.ok()).collect();
+///
+/// assert_eq!(cookies[0].value(), "hello");
+/// ```
+pub trait ValueDecoder: Send + Sync {
+    /// Decode a raw cookie value.
+    fn decode<'a>(&self, value: &'a str) -> Result<Cow<'a, str>, ParseError>;
+}
+
+impl fmt::Debug for dyn ValueDecoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ValueDecoder")
+    }
+}
+
+impl<F> ValueDecoder for F
+where
+    F: Fn(&str) -> Result<String, ParseError> + Send + Sync,
+{
+    fn decode<'a>(&self, value: &'a str) -> Result<Cow<'a, str>, ParseError> {
+        self(value).map(Cow::Owned)
+    }
+}
+
+/// A decoder that returns values unchanged.
+///
+/// This is the default decoder when the `percent-encode` feature is disabled.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IdentityDecoder;
+
+impl ValueDecoder for IdentityDecoder {
+    fn decode<'a>(&self, value: &'a str) -> Result<Cow<'a, str>, ParseError> {
+        Ok(Cow::Borrowed(value))
+    }
+}
+
+/// A decoder for percent-encoded values (e.g., `%20` for space).
+///
+/// This is the default decoder when the `percent-encode` feature is enabled. Values without
+/// any `%` escapes are returned borrowed, and escapes that decode to invalid UTF-8 are
+/// reported as [`ParseError::Utf8Error`].
+#[cfg(feature = "percent-encode")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PercentDecoder;
+
+#[cfg(feature = "percent-encode")]
+impl ValueDecoder for PercentDecoder {
+    fn decode<'a>(&self, value: &'a str) -> Result<Cow<'a, str>, ParseError> {
+        Ok(percent_encoding::percent_decode_str(value).decode_utf8()?)
+    }
+}
+
+/// The character set that percent-escapes are decoded into by a [`CharsetDecoder`].
+#[cfg(feature = "percent-encode")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum Charset {
+    /// Escapes encode UTF-8 bytes, exactly as decoded by [`PercentDecoder`].
+    #[default]
+    Utf8,
+    /// Each `%XX` escape encodes one ISO-8859-1 (Latin-1) character, so `caf%E9` is `café`.
+    Latin1,
+    /// `%uXXXX` escapes encode UTF-16 code units and `%XX` escapes Latin-1 characters, as
+    /// produced by JavaScript's `escape` and legacy ASP.NET, so `%u20AC` is `€`.
+    ///
+    /// Surrogate pairs spread over two escapes are combined, and unpaired surrogates are
+    /// replaced with `U+FFFD`.
+    Utf16,
+}
+
+/// A decoder for percent-encoded values whose escapes use a [`Charset`] other than UTF-8.
+///
+/// Legacy applications percent-encode Latin-1 or UTF-16 data, which [`PercentDecoder`]
+/// rejects because the decoded bytes aren't UTF-8. This decoder maps the escapes of the
+/// configured charset to characters instead, so it never fails except for [`Charset::Utf8`].
+/// Unescaped characters are kept as they are, and so are `%` signs that don't start a valid
+/// escape. Values without any `%` are returned borrowed.
+///
+/// # Example
+///
+/// ```
+/// use cookie::Cookie;
+/// use ri_cookie_header_string::{Charset, CharsetDecoder, HeaderStringParser};
+///
+/// let parser = HeaderStringParser::builder().decoder(CharsetDecoder::new(Charset::Latin1)).build();
+/// let cookies: Vec<Cookie> = parser.parse("a=caf%E9").filter_map(|result| result.ok()).collect();
+///
+/// assert_eq!(cookies[0].value(), "café");
+/// ```
+#[cfg(feature = "percent-encode")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CharsetDecoder {
+    charset: Charset,
+}
+
+#[cfg(feature = "percent-encode")]
+impl CharsetDecoder {
+    /// Create a decoder for escapes in the given charset.
+    pub fn new(charset: Charset) -> Self {
+        CharsetDecoder {
+            charset,
+        }
+    }
+}
+
+#[cfg(feature = "percent-encode")]
+impl ValueDecoder for CharsetDecoder {
+    fn decode<'a>(&self, value: &'a str) -> Result<Cow<'a, str>, ParseError> {
+        if self.charset == Charset::Utf8 {
+            return PercentDecoder.decode(value);
+        }
+        if !value.contains('%') {
+            return Ok(Cow::Borrowed(value));
+        }
+
+        // Latin-1 characters are single UTF-16 code units, so both charsets decode through UTF-16
+        let bytes = value.as_bytes();
+        let mut units = Vec::with_capacity(value.len());
+        let mut i = 0;
+        while i < value.len() {
+            if bytes[i] == b'%' {
+                let wide = (self.charset == Charset::Utf16 && bytes.get(i + 1) == Some(&b'u'))
+                    .then(|| parse_hex(value.get(i + 2..i + 6)))
+                    .flatten();
+                if let Some(unit) = wide {
+                    units.push(unit);
+                    i += 6;
+                    continue;
+                }
+                if let Some(byte) = parse_hex(value.get(i + 1..i + 3)) {
+                    units.push(byte);
+                    i += 3;
+                    continue;
+                }
+            }
+
+            let c = value[i..].chars().next().unwrap_or_default();
+            units.extend_from_slice(c.encode_utf16(&mut [0; 2]));
+            i += c.len_utf8();
+        }
+
+        Ok(Cow::Owned(char::decode_utf16(units).map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER)).collect()))
+    }
+}
+
+/// Parse a slice of hexadecimal digits, without a sign.
+#[cfg(feature = "percent-encode")]
+fn parse_hex(digits: Option<&str>) -> Option<u16> {
+    let digits = digits.filter(|d| d.bytes().all(|b| b.is_ascii_hexdigit()))?;
+    u16::from_str_radix(digits, 16).ok()
+}
+
+/// Decode a value with the decoder used when none is configured.
+#[inline]
+pub(crate) fn decode_default(value: &str) -> Result<Cow<'_, str>, ParseError> {
+    #[cfg(feature = "percent-encode")]
+    {
+        PercentDecoder.decode(value)
+    }
+    #[cfg(not(feature = "percent-encode"))]
+    {
+        IdentityDecoder.decode(value)
+    }
+}
+
+#[cfg(all(test, feature = "percent-encode"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn charset_decoder() {
+        let decode = |charset, value| CharsetDecoder::new(charset).decode(value).map(Cow::into_owned);
+
+        assert_eq!(decode(Charset::Latin1, "caf%E9%20%3b"), Ok("caf\u{e9} ;".into()));
+        assert_eq!(decode(Charset::Latin1, "%u20AC"), Ok("%u20AC".into()));
+        assert_eq!(decode(Charset::Utf16, "%u20AC%E9"), Ok("\u{20ac}\u{e9}".into()));
+        assert_eq!(decode(Charset::Utf16, "%uD83C%uDF6A"), Ok("\u{1f36a}".into()));
+        assert_eq!(decode(Charset::Utf16, "%uD83Cx"), Ok("\u{fffd}x".into()));
+        assert_eq!(decode(Charset::Utf16, "\u{e9}%2%zz%u+0FF%"), Ok("\u{e9}%2%zz%u+0FF%".into()));
+        assert_eq!(decode(Charset::Utf8, "caf%C3%A9"), Ok("caf\u{e9}".into()));
+        assert!(decode(Charset::Utf8, "caf%E9").is_err());
+
+        assert!(matches!(CharsetDecoder::new(Charset::Latin1).decode("plain"), Ok(Cow::Borrowed("plain"))));
+    }
+}
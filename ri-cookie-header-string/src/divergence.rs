@@ -0,0 +1,157 @@
+//! Comparison of the default parser with the `cookie` crate's `SplitCookies`.
+
+use crate::{HeaderParseError, HeaderStringParser};
+use alloc::vec::Vec;
+use cookie::{Cookie, ParseError};
+use core::ops::Range;
+
+/// A part of a header that this crate's default parser and [`SplitCookies`] read differently.
+///
+/// Created by [`diff_against_standard`].
+///
+/// [`SplitCookies`]: cookie::SplitCookies
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence<'c> {
+    /// The byte range of the header covered by both interpretations.
+    ///
+    /// This is the raw segment as read by this crate's parser, or the segment as read by
+    /// `SplitCookies` if this crate's parser skipped it.
+    pub range: Range<usize>,
+    /// The result of this crate's default parser, or `None` if it skipped the segment.
+    pub lenient: Option<Result<Cookie<'c>, HeaderParseError>>,
+    /// The results of `SplitCookies` for the same bytes, one per `;`-separated segment that
+    /// isn't blank.
+    pub standard: Vec<Result<Cookie<'c>, ParseError>>,
+}
+
+/// Parse a header with both this crate's default parser and the `cookie` crate's
+/// [`SplitCookies`], and report every segment where they disagree.
+///
+/// Two results agree when this crate's parser read a segment as exactly one `SplitCookies`
+/// segment and the resulting cookies, or errors, are equal. Anything else is a [`Divergence`]:
+/// a value that kept a `;` and so spans several standard segments, a segment one parser
+/// skipped, or a segment both read but differently. Values are percent-decoded on both sides
+/// when the `percent-encode` feature is enabled.
+///
+/// An empty result means the standard parser gives the same cookies for this header, so the
+/// non-standard heuristics aren't needed for it.
+///
+/// # Example
+///
+/// ```
+/// use ri_cookie_header_string::diff_against_standard;
+///
+/// assert!(diff_against_standard("a=1; b=2").is_empty());
+///
+/// let divergences = diff_against_standard("session=abc;123; b=2");
+/// assert_eq!(divergences.len(), 1);
+/// assert_eq!(divergences[0].range, 0..15);
+/// assert_eq!(divergences[0].lenient.as_ref().unwrap().as_ref().unwrap().value(), "abc;123");
+/// assert_eq!(divergences[0].standard.len(), 2);
+/// ```
+///
+/// [`SplitCookies`]: cookie::SplitCookies
+pub fn diff_against_standard(header: &str) -> Vec<Divergence<'_>> {
+    let mut standard = standard_segments(header).peekable();
+    let mut divergences = Vec::new();
+
+    for (range, lenient) in HeaderStringParser::new().parse::<Cookie, _>(header).indexed() {
+        // Standard segments before this one were skipped by the lenient parser
+        while let Some((skipped, result)) = standard.next_if(|(segment, _)| segment.start < range.start) {
+            divergences.push(Divergence {
+                range: skipped,
+                lenient: None,
+                standard: alloc::vec![result],
+            });
+        }
+
+        let mut covered = Vec::new();
+        while let Some((_, result)) = standard.next_if(|(segment, _)| segment.start < range.end) {
+            covered.push(result);
+        }
+
+        let agrees = match (&lenient, covered.as_slice()) {
+            (Ok(cookie), [Ok(other)]) => cookie == other,
+            (
+                Err(
+                    HeaderParseError::Cookie(error)
+                    | HeaderParseError::DecodeFailed {
+                        error,
+                        ..
+                    },
+                ),
+                [Err(other)],
+            ) => error == other,
+            _ => false,
+        };
+        if !agrees {
+            divergences.push(Divergence {
+                range,
+                lenient: Some(lenient),
+                standard: covered,
+            });
+        }
+    }
+
+    divergences.extend(standard.map(|(range, result)| Divergence {
+        range,
+        lenient: None,
+        standard: alloc::vec![result],
+    }));
+    divergences
+}
+
+/// Run `SplitCookies` over `header`, pairing each result with the range of its segment.
+fn standard_segments(header: &str) -> impl Iterator<Item = (Range<usize>, Result<Cookie<'_>, ParseError>)> {
+    // `SplitCookies` splits at every `;` and skips blank segments, but doesn't report ranges
+    let ranges = header
+        .split(';')
+        .scan(0, |start, segment| {
+            let range = *start..*start + segment.len();
+            *start = range.end + 1;
+            Some(range)
+        })
+        .filter(|range| !header[range.clone()].chars().all(char::is_whitespace));
+
+    #[cfg(feature = "percent-encode")]
+    let results = Cookie::split_parse_encoded(header);
+    #[cfg(not(feature = "percent-encode"))]
+    let results = Cookie::split_parse(header);
+
+    ranges.zip(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::{String, ToString};
+    use alloc::vec;
+
+    /// Summarize divergences as the lenient value and the standard values, errors as `!`.
+    fn summarize(header: &str) -> Vec<(Option<String>, Vec<String>)> {
+        diff_against_standard(header)
+            .into_iter()
+            .map(|d| {
+                let lenient = d.lenient.map(|r| r.map_or("!".to_string(), |c| c.value().to_string()));
+                let standard = d.standard.into_iter().map(|r| r.map_or("!".to_string(), |c| c.value().to_string()));
+                (lenient, standard.collect())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn diff_against_standard_reports_divergences() {
+        assert!(summarize("").is_empty());
+        assert!(summarize("a=1; b=2;; c=x=y; ").is_empty());
+
+        assert_eq!(summarize("a=x;y; b=2"), [(Some("x;y".into()), vec!["x".into(), "!".into()])]);
+        assert_eq!(summarize("a=1; flag; b=2"), [(Some("1; flag".into()), vec!["1".into(), "!".into()])]);
+        assert_eq!(summarize("a=\"x; y\"; b=2"), [(Some("x; y".into()), vec!["\"x".into(), "!".into()])]);
+        assert_eq!(summarize("=x; a=1"), [(None, vec!["!".into()])]);
+        assert_eq!(summarize("flag; a=1"), [(None, vec!["!".into()])]);
+
+        let divergences = diff_against_standard("a=1; b=x;y");
+        assert_eq!(divergences.len(), 1);
+        assert_eq!(divergences[0].range, 4..10);
+    }
+}
@@ -0,0 +1,111 @@
+//! Eager, callback-based parsing for hot paths.
+
+use crate::{HeaderParseError, HeaderStringCookies, HeaderStringParser};
+use alloc::borrow::Cow;
+use cookie::Cookie;
+
+impl HeaderStringParser {
+    /// Parse a cookie header string using this parser's configuration, calling `on_cookie` with
+    /// the name and decoded value of each cookie and `on_error` with each error.
+    ///
+    /// Unlike [`parse`](Self::parse), this processes the whole header eagerly before returning,
+    /// and neither constructs cookies nor wraps successes in `Option<Result<_>>`. Values are
+    /// borrowed from the header unless decoding changes them. The cookies and errors are reported
+    /// in header order, exactly as the iterator would yield them.
+    pub fn for_each_cookie<F, E>(&self, header: &str, mut on_cookie: F, mut on_error: E)
+    where
+        F: FnMut(&str, &str),
+        E: FnMut(HeaderParseError),
+    {
+        // The cookie type is unused; the iterator only supplies ranges.
+        let mut iter = HeaderStringCookies::<Cookie>::new(Cow::Borrowed(header), self.clone());
+
+        while let Some((_, ranges)) = iter.next_ranges() {
+            let result = ranges.and_then(|(name, value)| {
+                let name = &header[name];
+                Ok((name, self.decode(name, &header[value])?))
+            });
+
+            match result {
+                Ok((name, value)) => {
+                    iter.emitted += 1;
+                    on_cookie(&self.normalize_name(name), &value);
+                }
+                Err(err) => on_error(err),
+            }
+        }
+    }
+}
+
+/// Parse a cookie header string with the default configuration, calling `on_cookie` for each
+/// cookie and `on_error` for each error.
+///
+/// The header is processed eagerly. See [`HeaderStringParser::for_each_cookie`].
+///
+/// # Example
+///
+/// ```
+/// use ri_cookie_header_string::for_each_cookie;
+///
+/// let mut session = None;
+/// let mut errors = 0;
+/// for_each_cookie(
+///     "session=abc;123; theme=dark; =oops",
+///     |name, value| {
+///         if name == "session" {
+///             session = Some(value.to_string());
+///         }
+///     },
+///     |_| errors += 1,
+/// );
+///
+/// assert_eq!(session.as_deref(), Some("abc;123"));
+/// assert_eq!(errors, 0);
+/// ```
+pub fn for_each_cookie<F, E>(header: &str, on_cookie: F, on_error: E)
+where
+    F: FnMut(&str, &str),
+    E: FnMut(HeaderParseError),
+{
+    HeaderStringParser::new().for_each_cookie(header, on_cookie, on_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseMode;
+    use cookie::ParseError;
+
+    #[test]
+    fn for_each_cookie_matches_iterator() {
+        let cookie_header = "a=1; flag; b=x;y; =2; c=\"q\"";
+        let parser = HeaderStringParser::builder().mode(ParseMode::Strict).max_cookies(2).build();
+
+        let mut cookies = Vec::new();
+        let mut errors = Vec::new();
+        parser.for_each_cookie(
+            cookie_header,
+            |name, value| cookies.push((name.to_string(), value.to_string())),
+            |err| errors.push(err),
+        );
+
+        let expected: Vec<_> = parser.parse::<Cookie, _>(cookie_header).collect();
+        let expected_cookies: Vec<_> =
+            expected.iter().flatten().map(|c| (c.name().to_string(), c.value().to_string())).collect();
+        let expected_errors: Vec<_> = expected.into_iter().filter_map(Result::err).collect();
+
+        assert_eq!(cookies, expected_cookies);
+        assert_eq!(errors, expected_errors);
+        assert_eq!(
+            errors,
+            [
+                ParseError::MissingPair.into(),
+                ParseError::MissingPair.into(),
+                ParseError::EmptyName.into(),
+                HeaderParseError::TooManyCookies {
+                    limit: 2
+                }
+            ]
+        );
+    }
+}
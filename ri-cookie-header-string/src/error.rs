@@ -0,0 +1,240 @@
+//! Error type for cookie header string parsing.
+
+use crate::CookiePrefix;
+use alloc::string::String;
+use alloc::vec::Vec;
+use cookie::ParseError;
+use core::convert::Infallible;
+use core::error::Error;
+use core::fmt;
+use core::ops::Range;
+use core::str::Utf8Error;
+
+/// Enum corresponding to an error while parsing a cookie header string.
+///
+/// Wraps the `cookie` crate's [`ParseError`] and adds the failures that only this crate's
+/// parser can produce, such as configured limits being exceeded.
+///
+/// Before 0.3 the iterators yielded `cookie::ParseError` directly. Code matching on it can
+/// match on [`HeaderParseError::Cookie`] instead, and functions returning a `HeaderParseError`
+/// can still use `?` on a `cookie::ParseError` through the [`From`] conversion. The enum is
+/// non-exhaustive, so new variants can be added as options grow.
+///
+/// `Debug` prints the raw value of [`DecodeFailed`](HeaderParseError::DecodeFailed) as `***`, so
+/// errors can be logged without leaking session tokens or other secrets.
+#[derive(Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum HeaderParseError {
+    /// The cookie could not be parsed by the `cookie` crate, or the segment is structurally
+    /// malformed.
+    ///
+    /// Structural errors use the same [`ParseError`] the `cookie` crate reports for them: a
+    /// segment without a `=` is [`ParseError::MissingPair`] and one with an empty name is
+    /// [`ParseError::EmptyName`]. Neither carries the value, since the segment isn't a usable
+    /// cookie.
+    Cookie(ParseError),
+    /// The cookie is well-formed but its value could not be decoded by the parser's
+    /// [`ValueDecoder`](crate::ValueDecoder), e.g. a percent escape that decodes to invalid
+    /// UTF-8.
+    ///
+    /// Unlike structural errors, the cookie itself is usable: `raw` holds its value as it
+    /// appears in the header, which can be kept instead of rejecting the cookie. It is redacted
+    /// from the `Debug` output. See also
+    /// [`raw_on_decode_error`](crate::HeaderStringParserBuilder::raw_on_decode_error).
+    DecodeFailed {
+        /// The raw value, without the quotes of a quoted value.
+        raw: String,
+        /// The error returned by the decoder.
+        error: ParseError,
+    },
+    /// The cookie contained bytes that are not valid UTF-8 and invalid input is rejected.
+    ///
+    /// Only produced when parsing bytes with [`Utf8Mode::Strict`](crate::Utf8Mode::Strict).
+    InvalidUtf8(Utf8Error),
+    /// The header contained more cookies than the configured maximum.
+    ///
+    /// This error is terminal: the iterator yields `None` afterwards.
+    TooManyCookies {
+        /// The configured maximum number of cookies.
+        limit: usize,
+    },
+    /// The header string was longer than the configured maximum length in bytes.
+    ///
+    /// This error is terminal and is reported before any cookie is parsed.
+    HeaderTooLong {
+        /// The configured maximum header length in bytes.
+        limit: usize,
+    },
+    /// A cookie's value was longer than the configured maximum length in bytes.
+    ValueTooLong {
+        /// The configured maximum value length in bytes.
+        limit: usize,
+    },
+    /// A cookie's name was longer than the configured maximum length in bytes.
+    NameTooLong {
+        /// The configured maximum name length in bytes.
+        limit: usize,
+    },
+    /// A cookie's name has a `__Secure-` or `__Host-` prefix and prefixed names are rejected.
+    PrefixedName {
+        /// The prefix found on the name.
+        prefix: CookiePrefix,
+    },
+    /// A cookie's raw value contains a control character and control characters are rejected.
+    ControlCharacter {
+        /// The first control byte found in the value.
+        byte: u8,
+    },
+    /// A cookie's raw value contains a byte outside the RFC 6265 `cookie-octet` set and
+    /// values are validated.
+    InvalidCookieOctet {
+        /// The first invalid byte found in the value.
+        byte: u8,
+    },
+    /// A cookie's value consists only of whitespace and such values are rejected.
+    WhitespaceOnlyValue,
+    /// A cookie's raw value contains more `=` signs than the configured maximum.
+    TooManyEquals {
+        /// The configured maximum number of `=` signs in a value.
+        limit: usize,
+    },
+    /// The header was expected to contain exactly one cookie but contained a different number.
+    CookieCount {
+        /// The number of cookies found.
+        found: usize,
+    },
+    /// Every error found in the header, each with the byte range of its segment.
+    ///
+    /// Returned by the eager helpers when
+    /// [`collect_all_errors`](crate::HeaderStringParserBuilder::collect_all_errors) is enabled,
+    /// and by [`parse_all_or_reject`](crate::HeaderStringParser::parse_all_or_reject) to report
+    /// where the header was rejected. Ranges are shifted by the parser's
+    /// [`base_offset`](crate::HeaderStringParserBuilder::base_offset).
+    Multiple {
+        /// The errors in the order they occur in the header.
+        errors: Vec<(Range<usize>, HeaderParseError)>,
+    },
+}
+
+impl fmt::Debug for HeaderParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HeaderParseError::Cookie(err) => f.debug_tuple("Cookie").field(err).finish(),
+            HeaderParseError::DecodeFailed {
+                error,
+                ..
+            } => f.debug_struct("DecodeFailed").field("raw", &"***").field("error", error).finish(),
+            HeaderParseError::InvalidUtf8(err) => f.debug_tuple("InvalidUtf8").field(err).finish(),
+            HeaderParseError::TooManyCookies {
+                limit,
+            } => f.debug_struct("TooManyCookies").field("limit", limit).finish(),
+            HeaderParseError::HeaderTooLong {
+                limit,
+            } => f.debug_struct("HeaderTooLong").field("limit", limit).finish(),
+            HeaderParseError::ValueTooLong {
+                limit,
+            } => f.debug_struct("ValueTooLong").field("limit", limit).finish(),
+            HeaderParseError::NameTooLong {
+                limit,
+            } => f.debug_struct("NameTooLong").field("limit", limit).finish(),
+            HeaderParseError::PrefixedName {
+                prefix,
+            } => f.debug_struct("PrefixedName").field("prefix", prefix).finish(),
+            HeaderParseError::ControlCharacter {
+                byte,
+            } => f.debug_struct("ControlCharacter").field("byte", byte).finish(),
+            HeaderParseError::InvalidCookieOctet {
+                byte,
+            } => f.debug_struct("InvalidCookieOctet").field("byte", byte).finish(),
+            HeaderParseError::WhitespaceOnlyValue => f.write_str("WhitespaceOnlyValue"),
+            HeaderParseError::TooManyEquals {
+                limit,
+            } => f.debug_struct("TooManyEquals").field("limit", limit).finish(),
+            HeaderParseError::CookieCount {
+                found,
+            } => f.debug_struct("CookieCount").field("found", found).finish(),
+            HeaderParseError::Multiple {
+                errors,
+            } => f.debug_struct("Multiple").field("errors", errors).finish(),
+        }
+    }
+}
+
+impl fmt::Display for HeaderParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HeaderParseError::Cookie(err) => err.fmt(f),
+            HeaderParseError::DecodeFailed {
+                error,
+                ..
+            } => write!(f, "the cookie's value could not be decoded: {error}"),
+            HeaderParseError::InvalidUtf8(err) => write!(f, "the cookie is not valid UTF-8: {err}"),
+            HeaderParseError::TooManyCookies {
+                limit,
+            } => write!(f, "the header contains more than {limit} cookies"),
+            HeaderParseError::HeaderTooLong {
+                limit,
+            } => write!(f, "the header is longer than {limit} bytes"),
+            HeaderParseError::ValueTooLong {
+                limit,
+            } => write!(f, "the cookie's value is longer than {limit} bytes"),
+            HeaderParseError::NameTooLong {
+                limit,
+            } => write!(f, "the cookie's name is longer than {limit} bytes"),
+            HeaderParseError::PrefixedName {
+                prefix,
+            } => write!(f, "the cookie's name has the `{}` prefix", prefix.as_str()),
+            HeaderParseError::ControlCharacter {
+                byte,
+            } => write!(f, "the cookie's value contains the control character {byte:#04x}"),
+            HeaderParseError::InvalidCookieOctet {
+                byte,
+            } => write!(f, "the cookie's value contains the invalid octet {byte:#04x}"),
+            HeaderParseError::WhitespaceOnlyValue => f.write_str("the cookie's value consists only of whitespace"),
+            HeaderParseError::TooManyEquals {
+                limit,
+            } => write!(f, "the cookie's value contains more than {limit} `=` signs"),
+            HeaderParseError::CookieCount {
+                found,
+            } => write!(f, "expected exactly one cookie, found {found}"),
+            HeaderParseError::Multiple {
+                errors,
+            } => match errors.as_slice() {
+                [(range, error)] => write!(f, "the header contains an error at {range:?}: {error}"),
+                [(range, first), ..] => {
+                    write!(f, "the header contains {} errors, the first at {range:?}: {first}", errors.len())
+                }
+                [] => f.write_str("the header contains errors"),
+            },
+        }
+    }
+}
+
+impl Error for HeaderParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            HeaderParseError::Cookie(err) => Some(err),
+            HeaderParseError::DecodeFailed {
+                error,
+                ..
+            } => Some(error),
+            HeaderParseError::InvalidUtf8(err) => Some(err),
+            HeaderParseError::Multiple {
+                errors,
+            } => errors.first().map(|(_, err)| err as &(dyn Error + 'static)),
+            _ => None,
+        }
+    }
+}
+
+impl From<Infallible> for HeaderParseError {
+    fn from(err: Infallible) -> Self {
+        match err {}
+    }
+}
+
+impl From<ParseError> for HeaderParseError {
+    fn from(err: ParseError) -> Self {
+        HeaderParseError::Cookie(err)
+    }
+}
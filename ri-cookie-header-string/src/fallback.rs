@@ -0,0 +1,123 @@
+//! Strict parsing with a fallback to the lenient heuristics.
+
+use crate::{CookieBuilder, HeaderParseError, HeaderStringCookies, HeaderStringParser, ParseMode, is_token};
+use alloc::borrow::Cow;
+use cookie::{Cookie, ParseError};
+
+impl HeaderStringParser {
+    /// Parse a cookie header string in [`ParseMode::Strict`] if that splits it cleanly, and in
+    /// [`ParseMode::Lenient`] otherwise, returning the mode that was used along with the
+    /// iterator.
+    ///
+    /// The header is first scanned in strict mode without constructing any cookie. Lenient
+    /// parsing is used instead if that scan finds a sign that a semicolon inside a value was
+    /// taken as a separator:
+    ///
+    /// - a segment without a `=`, e.g. the `123` of `session=abc;123`,
+    /// - a segment with an empty name, e.g. the `=b` of `a=x;=b`, or
+    /// - a cookie name that is not a valid RFC 6265 token, e.g. the `y z` of `a=x;y z=1`.
+    ///
+    /// Other errors, such as exceeding a configured limit, don't trigger the fallback since
+    /// lenient parsing wouldn't avoid them. All other options of this parser apply in either
+    /// mode.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    /// use ri_cookie_header_string::{HeaderStringParser, ParseMode};
+    ///
+    /// let parser = HeaderStringParser::new();
+    ///
+    /// let (mode, cookies) = parser.parse_strict_then_lenient::<Cookie, _>("a=1; b=2");
+    /// assert_eq!(mode, ParseMode::Strict);
+    /// assert_eq!(cookies.count(), 2);
+    ///
+    /// let (mode, mut cookies) = parser.parse_strict_then_lenient::<Cookie, _>("session=abc;123; b=2");
+    /// assert_eq!(mode, ParseMode::Lenient);
+    /// assert_eq!(cookies.next().unwrap().unwrap().value(), "abc;123");
+    /// ```
+    pub fn parse_strict_then_lenient<'c, C, S>(&self, string: S) -> (ParseMode, HeaderStringCookies<'c, C>)
+    where
+        C: CookieBuilder<'c>,
+        S: Into<Cow<'c, str>>,
+    {
+        let string = string.into();
+        let strict = HeaderStringParser {
+            mode: ParseMode::Strict,
+            ..self.clone()
+        };
+
+        let mode = if splits_cleanly(&string, &strict) {
+            ParseMode::Strict
+        } else {
+            ParseMode::Lenient
+        };
+        let parser = HeaderStringParser {
+            mode,
+            ..strict
+        };
+
+        (mode, HeaderStringCookies::new(string, parser))
+    }
+}
+
+/// Check that strict parsing of `header` shows no sign of a mis-split.
+fn splits_cleanly(header: &str, strict: &HeaderStringParser) -> bool {
+    // The cookie type is unused; the iterator only supplies ranges.
+    let mut iter = HeaderStringCookies::<Cookie>::new(Cow::Borrowed(header), strict.clone());
+
+    while let Some((_, ranges)) = iter.next_ranges() {
+        match ranges {
+            Ok((name, _)) if !is_token(&header[name.start..name.end]) => return false,
+            Ok(_) => iter.emitted += 1,
+            Err(HeaderParseError::Cookie(ParseError::MissingPair | ParseError::EmptyName)) => return false,
+            Err(_) => {}
+        }
+    }
+
+    true
+}
+
+/// Parse a cookie header string strictly if that splits it cleanly, and leniently otherwise,
+/// using the default configuration.
+///
+/// See [`HeaderStringParser::parse_strict_then_lenient`] for the conditions that trigger the
+/// fallback.
+pub fn parse_strict_then_lenient(header: &str) -> (ParseMode, HeaderStringCookies<'_, Cookie<'_>>) {
+    HeaderStringParser::new().parse_strict_then_lenient(header)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Assert the mode chosen for `header` and the names and values of the cookies it yields.
+    #[track_caller]
+    fn assert_parses(parser: &HeaderStringParser, header: &str, mode: ParseMode, expected: &[(&str, &str)]) {
+        let (actual_mode, cookies) = parser.parse_strict_then_lenient::<Cookie, _>(header);
+        let cookies: Vec<_> = cookies.flatten().collect();
+        let actual: Vec<_> = cookies.iter().map(Cookie::name_value).collect();
+        assert_eq!((actual_mode, actual.as_slice()), (mode, expected), "header: {header:?}");
+    }
+
+    #[test]
+    fn falls_back_on_mis_split() {
+        let parser = HeaderStringParser::new();
+
+        assert_parses(&parser, "", ParseMode::Strict, &[]);
+        assert_parses(&parser, "a=1; b=2;", ParseMode::Strict, &[("a", "1"), ("b", "2")]);
+        assert_parses(&parser, "a=x=y;b=2", ParseMode::Strict, &[("a", "x=y"), ("b", "2")]);
+        assert_parses(&parser, "a=abc;123; b=2", ParseMode::Lenient, &[("a", "abc;123"), ("b", "2")]);
+        assert_parses(&parser, "a=x;=y; b=2", ParseMode::Lenient, &[("a", "x;=y"), ("b", "2")]);
+        assert_parses(&parser, "a=x;y z=1; b=2", ParseMode::Lenient, &[("a", "x;y z=1"), ("b", "2")]);
+
+        // Limits are not a sign of a mis-split
+        let limited = HeaderStringParser::builder().max_cookies(1).build();
+        let (mode, cookies) = limited.parse_strict_then_lenient::<Cookie, _>("a=1; b=2");
+        assert_eq!(mode, ParseMode::Strict);
+        assert_eq!(cookies.filter(Result::is_err).count(), 1);
+
+        assert_eq!(parse_strict_then_lenient("a=1;2").0, ParseMode::Lenient);
+    }
+}
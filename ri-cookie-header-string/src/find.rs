@@ -0,0 +1,87 @@
+//! Early-terminating lookup of a single cookie by name.
+
+use crate::{CookieBuilder, HeaderStringCookies, HeaderStringParser};
+use cookie::Cookie;
+
+impl<'c, C: CookieBuilder<'c>> HeaderStringCookies<'c, C> {
+    /// Advance to the first cookie called `name` and return it, stopping immediately.
+    ///
+    /// Other cookies are scanned with the usual heuristics but never constructed or decoded, so
+    /// looking up a single cookie doesn't pay for the rest of the header. Segments that fail to
+    /// parse are skipped. Calling this again continues the search after the returned cookie.
+    pub fn find_cookie(&mut self, name: &str) -> Option<C> {
+        while let Some((_, ranges)) = self.next_ranges() {
+            let Ok((name_range, value_range)) = ranges else {
+                continue;
+            };
+            if self.parser.normalize_name(&self.string[name_range.clone()]) != name {
+                continue;
+            }
+
+            if let Ok(cookie) = self.build_cookie(name_range, value_range) {
+                self.emitted += 1;
+                return Some(cookie);
+            }
+        }
+
+        None
+    }
+}
+
+/// Find the first cookie called `name` in a header string using the default configuration.
+///
+/// See [`HeaderStringCookies::find_cookie`].
+///
+/// # Example
+///
+/// ```
+/// use ri_cookie_header_string::find_cookie;
+///
+/// let session = find_cookie("theme=dark; session=abc;123; lang=en", "session").unwrap();
+///
+/// assert_eq!(session.value(), "abc;123");
+/// assert!(find_cookie("theme=dark", "session").is_none());
+/// ```
+pub fn find_cookie<'c>(header: &'c str, name: &str) -> Option<Cookie<'c>> {
+    HeaderStringParser::new().parse(header).find_cookie(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NameValue;
+    use std::cell::Cell;
+
+    thread_local! {
+        static CONSTRUCTED: Cell<usize> = const { Cell::new(0) };
+    }
+
+    /// A cookie type that counts how many instances were constructed.
+    struct Counted(String, String);
+
+    impl CookieBuilder<'_> for Counted {
+        fn new(name: String, value: String) -> Self {
+            CONSTRUCTED.set(CONSTRUCTED.get() + 1);
+            Counted(name, value)
+        }
+    }
+
+    impl NameValue for Counted {
+        fn name_value(&self) -> (&str, &str) {
+            (&self.0, &self.1)
+        }
+    }
+
+    #[test]
+    fn find_cookie_stops_at_first_match() {
+        let mut iter = HeaderStringParser::new().parse::<Counted, _>("a=1; b=2; target=x;y; c=3; target=4");
+
+        let found = iter.find_cookie("target").unwrap();
+        assert_eq!(found.name_value(), ("target", "x;y"));
+        assert_eq!(CONSTRUCTED.get(), 1);
+
+        assert_eq!(iter.find_cookie("target").unwrap().name_value(), ("target", "4"));
+        assert_eq!(CONSTRUCTED.get(), 2);
+        assert!(iter.find_cookie("target").is_none());
+    }
+}
@@ -0,0 +1,214 @@
+//! A stored cookie header that can be iterated repeatedly.
+
+use crate::{CookieBuilder, HeaderParseError, HeaderStringCookies, HeaderStringErrors, HeaderStringParser};
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use cookie::Cookie;
+use core::convert::Infallible;
+use core::str::FromStr;
+
+/// A cookie header string together with the parser configuration used to read it.
+///
+/// Unlike [`HeaderStringCookies`], which is a one-shot cursor, a `CookieHeader` can be stored,
+/// passed around and iterated any number of times; each iteration starts a new cursor over the
+/// same string.
+///
+/// A `CookieHeader<'static>` can also be created with [`str::parse`] through its [`FromStr`]
+/// implementation, which copies the string and uses the default lenient settings, exactly like
+/// [`CookieHeader::new`]. Parsing never fails: as with the iterators, a malformed segment
+/// doesn't reject the header but is kept as an error in its place, so it is yielded when
+/// iterating and listed by [`errors`](Self::errors). Use [`with_parser`](Self::with_parser)
+/// for any other configuration. The error type is [`Infallible`], which `?` converts into a
+/// [`HeaderParseError`]:
+///
+/// ```
+/// use ri_cookie_header_string::{CookieHeader, HeaderParseError};
+///
+/// # fn main() -> Result<(), HeaderParseError> {
+/// let header: CookieHeader = "session=abc;123; theme=dark".parse()?;
+///
+/// assert_eq!(header.iter::<cookie::Cookie>().flatten().count(), 2);
+/// assert_eq!(header.errors().count(), 0);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Example
+///
+/// ```
+/// use ri_cookie_header_string::{CookieHeader, HeaderStringParser};
+///
+/// let parser = HeaderStringParser::builder().max_cookies(10).build();
+/// let header = CookieHeader::with_parser("session=abc;123; theme=dark", parser);
+///
+/// assert!((&header).into_iter().flatten().any(|cookie| cookie.name() == "theme"));
+///
+/// for cookie in &header {
+///     let cookie = cookie.unwrap();
+///     println!("{} = {}", cookie.name(), cookie.value());
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CookieHeader<'c> {
+    string: Cow<'c, str>,
+    parser: HeaderStringParser,
+}
+
+impl<'c> CookieHeader<'c> {
+    /// Create a header that is parsed with the default configuration.
+    pub fn new<S>(string: S) -> Self
+    where
+        S: Into<Cow<'c, str>>,
+    {
+        Self::with_parser(string, HeaderStringParser::new())
+    }
+
+    /// Create a header that is parsed with the given parser configuration.
+    pub fn with_parser<S>(string: S, parser: HeaderStringParser) -> Self
+    where
+        S: Into<Cow<'c, str>>,
+    {
+        CookieHeader {
+            string: string.into(),
+            parser,
+        }
+    }
+
+    /// Return the header string.
+    pub fn as_str(&self) -> &str {
+        &self.string
+    }
+
+    /// Return the parser configuration used to read the header.
+    pub fn parser(&self) -> &HeaderStringParser {
+        &self.parser
+    }
+
+    /// Start a new iteration over the header, producing cookies of any [`CookieBuilder`] type.
+    pub fn iter<'a, C>(&'a self) -> HeaderStringCookies<'a, C>
+    where
+        C: CookieBuilder<'a>,
+    {
+        self.parser.parse(self.as_str())
+    }
+
+    /// Return the errors encountered while parsing the header, in order.
+    pub fn errors(&self) -> HeaderStringErrors<'_, Cookie<'_>> {
+        self.iter().errors()
+    }
+
+    /// Keep only the cookies for which `f` returns `true`, given each cookie's name and
+    /// decoded value, and rewrite the header to hold just those.
+    ///
+    /// The header is re-serialized with [`to_header_string`](Self::to_header_string), so
+    /// segments that fail to parse are dropped too. This is how a proxy strips cookies before
+    /// forwarding a header upstream.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ri_cookie_header_string::CookieHeader;
+    ///
+    /// let mut header = CookieHeader::new("__Host-id=secret; theme=dark; __Secure-token=t; lang=en");
+    /// header.retain(|name, _| !name.starts_with("__"));
+    ///
+    /// assert_eq!(header.as_str(), "theme=dark; lang=en");
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&str, &str) -> bool,
+    {
+        let kept: Vec<Cookie> =
+            self.iter().flatten().filter(|cookie: &Cookie| f(cookie.name(), cookie.value())).collect();
+        let string = self.parser.to_header_string(kept);
+        self.string = Cow::Owned(string);
+    }
+
+    /// Serialize the cookies of the header with the parser's
+    /// [`to_header_string`](HeaderStringParser::to_header_string), dropping segments that fail
+    /// to parse.
+    pub fn to_header_string(&self) -> String {
+        self.parser.to_header_string(self.iter::<Cookie>().flatten())
+    }
+}
+
+/// Copy the string into an owned header that is parsed with the default lenient settings.
+///
+/// See [`CookieHeader`] for how errors are kept.
+impl FromStr for CookieHeader<'static> {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(CookieHeader::new(String::from(s)))
+    }
+}
+
+impl<'a> IntoIterator for &'a CookieHeader<'_> {
+    type Item = Result<Cookie<'a>, HeaderParseError>;
+    type IntoIter = HeaderStringCookies<'a, Cookie<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CookieHeaderStringExt;
+
+    #[test]
+    fn cookie_header_iterates_repeatedly() {
+        let header = CookieHeader::new(String::from("a=1; b=x;y"));
+
+        let first: Vec<_> = (&header).into_iter().flatten().collect();
+        let second: Vec<_> = header.iter::<Cookie>().flatten().collect();
+
+        assert_eq!(first, second);
+        let actual: Vec<_> = first.iter().map(|c| c.name_value()).collect();
+        assert_eq!(actual, [("a", "1"), ("b", "x;y")]);
+    }
+
+    #[test]
+    fn cookie_header_from_str() {
+        let header: CookieHeader = "a=1; b=x;y; c=\"q;r\"".parse().unwrap();
+        let expected: Vec<_> = Cookie::header_string_parse("a=1; b=x;y; c=\"q;r\"").collect();
+        assert_eq!(header.iter::<Cookie>().collect::<Vec<_>>(), expected);
+        assert_eq!(header.errors().count(), 0);
+
+        // Errors are kept in place rather than failing the parse
+        #[cfg(feature = "percent-encode")]
+        {
+            let header: CookieHeader = "a=%FF; b=2".parse().unwrap();
+            let results: Vec<Result<Cookie, _>> = header.iter().collect();
+            assert!(matches!(results[0], Err(HeaderParseError::DecodeFailed { .. })));
+            assert_eq!(results[1].as_ref().map(Cookie::name_value), Ok(("b", "2")));
+            assert_eq!(header.errors().count(), 1);
+        }
+
+        let parse = |s: &str| -> Result<CookieHeader<'static>, HeaderParseError> { Ok(s.parse()?) };
+        assert_eq!(parse("a=1").unwrap().as_str(), "a=1");
+    }
+
+    #[test]
+    fn cookie_header_retain() {
+        let parser = HeaderStringParser::builder().encode_on_serialize(false).build();
+        let mut header = CookieHeader::with_parser("=x; flag; a=1; secret=s; b=x;y", parser);
+        assert_eq!(header.to_header_string(), "a=1; secret=s; b=x%3By");
+
+        // Semicolons are escaped even with encoding off, so the value no longer splits
+        header.retain(|name, _| name != "secret");
+        assert_eq!(header.as_str(), "a=1; b=x%3By");
+        let values: Vec<_> = header.iter::<Cookie>().flatten().map(|c| c.value().to_string()).collect();
+        #[cfg(not(feature = "percent-encode"))]
+        assert_eq!(values, ["1", "x%3By"]);
+        #[cfg(feature = "percent-encode")]
+        assert_eq!(values, ["1", "x;y"]);
+
+        header.retain(|_, value| value.starts_with('x'));
+        assert_eq!(header.as_str(), "b=x%3By");
+        header.retain(|_, _| false);
+        assert_eq!(header.as_str(), "");
+    }
+}
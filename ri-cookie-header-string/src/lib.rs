@@ -13,11 +13,18 @@
 //! - **Advanced semicolon handling**: Distinguishes between semicolons that are cookie separators
 //!   and semicolons that appear within unquoted cookie values
 //! - **Iterator-based parsing**: Lazy evaluation returns an iterator over parsed cookies
-//! - **Error handling**: Returns `Result<Cookie, ParseError>` for each cookie, allowing
+//! - **Error handling**: Returns `Result<Cookie, HeaderParseError>` for each cookie, allowing
 //!   graceful handling of malformed entries
 //! - **Percent-encoding support**: Enable the `percent-encode` feature to decode percent-encoded
-//!   cookie values (e.g., `%20` for space)
+//!   cookie values (e.g., `%20` for space), or plug in another encoding with a [`ValueDecoder`]
 //! - **Multiple cookie implementations**: Support for `cookie` crate and optionally `reqwest` via feature flag
+//! - **`http` interop**: Enable the `http` feature to parse an `http::HeaderValue` directly
+//! - **Typed headers**: Enable the `headers` feature to build a `headers::Cookie`, as used by
+//!   axum's `TypedHeader`, through this parser's heuristics
+//! - **Zero-copy pairs**: [`name_value_pairs`] yields borrowed `(name, value)` slices without building cookies
+//! - **Raw byte input**: Parse headers that are not valid UTF-8, lossily or reporting the affected cookies
+//! - **Serde support**: Enable the `serde` feature to serialize parsed cookie sets via [`ParsedCookies`]
+//! - **WebAssembly**: Enable the `wasm` feature for a string-in, JSON-out entry point
 //!
 //! # When to Use This Library
 //!
@@ -36,7 +43,7 @@
 //!
 //! ```toml
 //! [dependencies]
-//! ri-cookie-header-string = "0.2"
+//! ri-cookie-header-string = "0.3"
 //! cookie = "0.18"
 //! ```
 //!
@@ -44,15 +51,22 @@
 //!
 //! ```toml
 //! [dependencies]
-//! ri-cookie-header-string = { version = "0.2", features = ["percent-encode"] }
+//! ri-cookie-header-string = { version = "0.3", features = ["percent-encode"] }
 //! cookie = "0.18"
 //! ```
 //!
+//! The crate is `no_std` + `alloc` when the default `std` feature is disabled, which removes
+//! the `HashMap`-based collectors and the `http`, `headers` and `reqwest` integrations. The
+//! `cookie` crate itself still requires `std`.
+//!
+//! The `indexmap` feature adds collectors into an `IndexMap`, which keep the order of the
+//! header while deduplicating names.
+//!
 //! For reqwest support, enable the `reqwest` feature:
 //!
 //! ```toml
 //! [dependencies]
-//! ri-cookie-header-string = { version = "0.2", features = ["reqwest"] }
+//! ri-cookie-header-string = { version = "0.3", features = ["reqwest"] }
 //! reqwest = { version = "0.12", features = ["cookies"] }
 //! ```
 //!
@@ -89,23 +103,118 @@
 //! assert_eq!(cookies[1].value(), "value");
 //! ```
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+/// Emit a trace-level event when the `tracing` feature is enabled, and nothing otherwise.
+///
+/// Events describe parser decisions by byte offsets and never include cookie values.
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::trace!($($arg)*);
+    };
+}
+
+mod arena;
+mod boundary;
+mod collect;
+mod decode;
+mod divergence;
+mod each;
+mod error;
+mod fallback;
+mod find;
+mod header;
+mod multi;
+mod one;
+mod pairs;
+mod parser;
+mod prefix;
+mod redact;
+mod serialize;
+mod set_cookie;
+#[cfg(feature = "futures")]
+mod stream;
+mod summary;
+mod validate;
+
+pub use arena::{ArenaHeaderStringCookies, StrAllocator};
+pub use boundary::{BoundaryStrategy, DefaultStrategy};
+pub use collect::{DuplicatePolicy, NameOrder, ParsedCookies};
+#[cfg(feature = "percent-encode")]
+pub use decode::{Charset, CharsetDecoder, PercentDecoder};
+pub use decode::{IdentityDecoder, ValueDecoder};
+pub use divergence::{Divergence, diff_against_standard};
+pub use each::for_each_cookie;
+pub use error::HeaderParseError;
+pub use fallback::parse_strict_then_lenient;
+pub use find::find_cookie;
+pub use header::CookieHeader;
+pub use multi::{MultiHeaderStringCookies, parse_multi};
+pub use one::{parse_cookie_pair, parse_one};
+pub use pairs::{NameValuePairs, OwnedPairs, header_string_parse_pairs_owned, name_value_pairs};
+pub use parser::{
+    HeaderStringParser, HeaderStringParserBuilder, LegacyAttributes, NameChars, ParseMode, Utf8Mode, WhitespaceValues,
+};
+pub use prefix::CookiePrefix;
+pub use redact::redact;
+pub use serialize::{CookiePair, to_header_string};
+pub use set_cookie::{SetCookieList, parse_set_cookie, parse_set_cookie_list};
+#[cfg(feature = "futures")]
+pub use stream::{CookieStream, parse_stream};
+pub use summary::ParseSummary;
+pub use validate::{count_cookies, parse_all_or_reject};
+
+use alloc::borrow::Cow;
+use alloc::collections::VecDeque;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 use cookie::{Cookie, ParseError};
-use std::borrow::Cow;
+use core::ops::Range;
+use core::str::Utf8Error;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
 
 /// Internal trait for abstracting cookie construction across different cookie implementations.
 ///
 /// This trait allows the parser to work with different cookie types (e.g., `cookie::Cookie`,
 /// `reqwest::cookie::Cookie`) by providing a common interface for creating cookies.
-pub trait CookieBuilder: Sized {
+///
+/// The `'c` lifetime is the lifetime of the header string being parsed, which allows
+/// implementations to borrow names and values from it instead of allocating.
+pub trait CookieBuilder<'c>: Sized {
     /// Create a new cookie with the given name and value.
     fn new(name: String, value: String) -> Self;
 
-    /// Create a cookie from a percent-encoded string.
+    /// Create a new cookie with a name and value borrowed from the header string.
     ///
-    /// This is only called when the `percent-encode` feature is enabled
-    /// and the cookie value contains `%` characters.
-    #[cfg(feature = "percent-encode")]
-    fn parse_encoded(cookie_str: String) -> Result<Self, ParseError>;
+    /// This is called when the source string is borrowed and no decoding is required.
+    /// The default implementation allocates and delegates to [`CookieBuilder::new`].
+    fn new_borrowed(name: &'c str, value: &'c str) -> Self {
+        Self::new(name.to_string(), value.to_string())
+    }
+
+    /// Apply a legacy RFC 2965 attribute such as `$Path` that followed this cookie in the header.
+    ///
+    /// Only called when the parser is configured with [`LegacyAttributes::Attach`]. The name
+    /// includes the leading `$`, and the value is raw with any surrounding quotes removed. The
+    /// default implementation ignores the attribute.
+    fn set_legacy_attribute(&mut self, name: &str, value: &str) {
+        let _ = (name, value);
+    }
+}
+
+/// Read access to a parsed cookie's name and value.
+///
+/// The collectors that key cookies by name, such as
+/// [`collect_map`](HeaderStringCookies::collect_map) and
+/// [`summarize`](HeaderStringCookies::summarize), need it on top of [`CookieBuilder`]. It is a
+/// separate trait so that a cookie type only has to implement [`CookieBuilder`] to be parsed.
+pub trait NameValue {
+    /// Return the cookie's name and value.
+    fn name_value(&self) -> (&str, &str);
 }
 
 /// Iterator over cookies in a header string.
@@ -115,247 +224,1830 @@ pub trait CookieBuilder: Sized {
 /// real-world edge cases in cookie parsing.
 ///
 /// Based on the `cookie` crate's `SplitCookies` iterator with enhanced heuristics.
-pub struct HeaderStringCookies<'c, C: CookieBuilder> {
+///
+/// A header made only of whitespace and separators, including the empty string, yields no
+/// items in any mode: no cookies and no errors, whatever limits are configured, except that
+/// [`max_header_len`](HeaderStringParserBuilder::max_header_len) still applies to whitespace.
+/// [`is_empty`](Self::is_empty) checks for this before iterating.
+///
+/// Cloning the iterator snapshots its position: the clone continues independently from the
+/// same point. A borrowed header string is not copied.
+pub struct HeaderStringCookies<'c, C: CookieBuilder<'c>> {
     // The source string, which we split and parse.
     string: Cow<'c, str>,
+    // Whether the source string is pure ASCII, so that it can be scanned byte by byte.
+    ascii: bool,
     // The index where we last split off.
     last: usize,
+    // The parser configuration driving this iterator.
+    parser: HeaderStringParser,
+    // The number of cookies produced so far.
+    emitted: usize,
+    // Counts of segments skipped without an error, reported by `stats()`.
+    empty_skipped: usize,
+    missing_equals_skipped: usize,
+    empty_name_skipped: usize,
+    // How the most recently scanned segment was terminated.
+    split: SplitKind,
+    // Replaced invalid UTF-8 sequences to reject, in order, when parsing bytes strictly.
+    invalid_utf8: Vec<(Range<usize>, Utf8Error)>,
+    // Name and value ranges of the legacy `$` attributes following the most recent cookie.
+    legacy_attributes: Vec<(Range<usize>, Range<usize>)>,
+    // The remaining segments, scanned ahead of time once iteration from the back has started.
+    buffered: Option<VecDeque<BufferedSegment>>,
     // Phantom data to hold the cookie builder type
-    _phantom: std::marker::PhantomData<C>,
+    _phantom: core::marker::PhantomData<C>,
+}
+
+// Implemented by hand so that cloning doesn't require `C: Clone`.
+impl<'c, C: CookieBuilder<'c>> Clone for HeaderStringCookies<'c, C> {
+    fn clone(&self) -> Self {
+        HeaderStringCookies {
+            string: self.string.clone(),
+            ascii: self.ascii,
+            last: self.last,
+            parser: self.parser.clone(),
+            emitted: self.emitted,
+            empty_skipped: self.empty_skipped,
+            missing_equals_skipped: self.missing_equals_skipped,
+            empty_name_skipped: self.empty_name_skipped,
+            split: self.split,
+            invalid_utf8: self.invalid_utf8.clone(),
+            legacy_attributes: self.legacy_attributes.clone(),
+            buffered: self.buffered.clone(),
+            _phantom: core::marker::PhantomData,
+        }
+    }
+}
+
+/// The byte ranges of a cookie's name and raw value, or the error for its segment.
+type CookieRanges = Result<(Range<usize>, Range<usize>), HeaderParseError>;
+
+/// The outcome of reading one segment between separators.
+pub(crate) enum SegmentRead {
+    /// The segment holds a cookie with these ranges, or an error to report for it.
+    Cookie(CookieRanges),
+    /// The segment is skipped without an error.
+    Skipped(SkipReason),
+}
+
+/// Why a segment was skipped without an error.
+pub(crate) enum SkipReason {
+    /// The segment is empty or only whitespace.
+    Empty,
+    /// The segment has no `=` and valueless cookies are not allowed.
+    MissingEquals,
+    /// The cookie's name is empty.
+    EmptyName,
+}
+
+/// A scanned segment's range, its cookie ranges, its legacy attribute ranges and how it was split.
+type BufferedSegment = (Range<usize>, CookieRanges, Vec<(Range<usize>, Range<usize>)>, SplitKind);
+
+/// Return the length of `s` without a trailing line terminator.
+///
+/// A header copied from a raw request may still end in `\r\n`, which is never part of the last
+/// cookie, even when values are not trimmed.
+#[inline]
+fn content_len(s: &str) -> usize {
+    s.trim_end_matches(['\r', '\n']).len()
+}
+
+/// Return the index just past a leading `Cookie:` or `Set-Cookie:` header name, or 0.
+///
+/// Names are matched ASCII case-insensitively. A cookie named `Cookie` is never mistaken for
+/// the header name, since its name is followed by `=` rather than `:`.
+fn header_name_end(s: &str) -> usize {
+    match s.split_once(':') {
+        Some((name, _)) if ["cookie", "set-cookie"].iter().any(|header| name.trim().eq_ignore_ascii_case(header)) => {
+            name.len() + 1
+        }
+        _ => 0,
+    }
+}
+
+/// Narrow `range` within `s` so that it excludes leading and trailing whitespace.
+#[inline]
+fn trim_range(s: &str, range: Range<usize>) -> Range<usize> {
+    let slice = &s[range.clone()];
+    let start = range.start + (slice.len() - slice.trim_start().len());
+    let end = range.end - (slice.len() - slice.trim_end().len());
+    start..end.max(start)
+}
+
+/// Replace each invalid UTF-8 sequence in `bytes` with `U+FFFD`, returning the string and, in
+/// [`Utf8Mode::Strict`], the position of each replacement.
+fn replace_invalid_utf8(bytes: &[u8], mode: Utf8Mode) -> (String, Vec<(Range<usize>, Utf8Error)>) {
+    let mut string = String::with_capacity(bytes.len());
+    let mut invalid_utf8 = Vec::new();
+
+    for chunk in bytes.utf8_chunks() {
+        string.push_str(chunk.valid());
+        if chunk.invalid().is_empty() {
+            continue;
+        }
+
+        let start = string.len();
+        string.push(char::REPLACEMENT_CHARACTER);
+        if mode == Utf8Mode::Strict
+            && let Err(err) = core::str::from_utf8(chunk.invalid())
+        {
+            invalid_utf8.push((start..string.len(), err));
+        }
+    }
+
+    (string, invalid_utf8)
+}
+
+/// Narrow `range` within the bytes of an all-ASCII string so that it excludes leading and
+/// trailing whitespace.
+///
+/// This agrees with [`trim_range`] on ASCII input, whose only whitespace characters are the
+/// space and `\t` through `\r`, without decoding characters or checking char boundaries.
+#[inline]
+fn trim_range_ascii(bytes: &[u8], range: Range<usize>) -> Range<usize> {
+    let is_whitespace = |b: &&u8| matches!(b, b'\t'..=b'\r' | b' ');
+    let slice = &bytes[range.clone()];
+    let start = range.start + slice.iter().take_while(is_whitespace).count();
+    let end = range.end - slice.iter().rev().take_while(is_whitespace).count();
+    start..end.max(start)
+}
+
+/// Slice `s` by a byte range, reporting an error instead of panicking if the range does not
+/// fall on char boundaries.
+///
+/// All ranges are derived from ASCII delimiters, so this only fails if that invariant is broken.
+#[inline]
+fn checked_slice(s: &str, range: Range<usize>) -> Result<&str, ParseError> {
+    match s.get(range.clone()) {
+        Some(slice) => Ok(slice),
+        None => {
+            let bytes = s.as_bytes().get(range).ok_or(ParseError::MissingPair)?;
+            Ok(core::str::from_utf8(bytes)?)
+        }
+    }
+}
+
+/// Find the index of the closing double quote of a quoted string starting at `start`.
+///
+/// Returns `None` if `bytes[start]` is not a double quote or the quote is never closed.
+/// A backslash escapes the following byte, so `\"` does not close the string.
+fn find_closing_quote(bytes: &[u8], start: usize) -> Option<usize> {
+    find_closing(bytes, start, b'"')
+}
+
+/// Find the index of the `quote` closing a string quoted with it that starts at `start`.
+fn find_closing(bytes: &[u8], start: usize, quote: u8) -> Option<usize> {
+    if bytes.get(start) != Some(&quote) {
+        return None;
+    }
+
+    let mut k = start + 1;
+    while k < bytes.len() {
+        match bytes[k] {
+            b'\\' => k += 2,
+            b if b == quote => return Some(k),
+            _ => k += 1,
+        }
+    }
+
+    None
+}
+
+/// Check if `s` is a valid RFC 6265 cookie name, i.e. a non-empty RFC 7230 `token`.
+fn is_token(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(is_token_byte)
+}
+
+/// Check if `b` is an RFC 7230 `tchar`.
+#[inline]
+fn is_token_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric()
+        || matches!(
+            b,
+            b'!' | b'#' | b'$' | b'%' | b'&' | b'\'' | b'*' | b'+' | b'-' | b'.' | b'^' | b'_' | b'`' | b'|' | b'~'
+        )
+}
+
+/// Check if the separator at `sep` is followed by an optional space and then `name=`.
+///
+/// This is a cheap, bounded check for the common case of a well-formed header. It only
+/// accepts cases that the full lookahead heuristics would also treat as a separator.
+#[inline]
+fn is_clean_separator(bytes: &[u8], sep: usize, chars: NameChars) -> bool {
+    let mut start = sep + 1;
+    if bytes.get(start) == Some(&b' ') {
+        start += 1;
+    }
+
+    let name_len = bytes[start..].iter().take_while(|&&b| chars.allows(b)).count();
+    name_len > 0 && bytes.get(start + name_len) == Some(&b'=')
+}
+
+/// Check if the comma at `comma` separates two cookies folded into one header, which is the case
+/// when it is followed, after optional whitespace, by a name made of `chars` and then a `=`.
+fn is_comma_separator(bytes: &[u8], comma: usize, sep: u8, chars: NameChars) -> bool {
+    let start = skip_whitespace(bytes, comma + 1, sep);
+    let name_len = bytes[start..].iter().take_while(|&&b| chars.allows(b)).count();
+    name_len > 0 && bytes.get(skip_whitespace(bytes, start + name_len, sep)) == Some(&b'=')
+}
+
+/// Return the index of the first byte at or after `i` that doesn't start a whitespace character,
+/// treating `sep` as non-whitespace so a whitespace separator such as a newline is not skipped.
+///
+/// Whitespace is Unicode whitespace, the same as for [`str::trim`], so that skipping ahead
+/// agrees with how segments are trimmed: tabs, vertical tabs, form feeds and non-breaking
+/// spaces all count.
+fn skip_whitespace(bytes: &[u8], mut i: usize, sep: u8) -> usize {
+    while let Some(&b) = bytes.get(i) {
+        // Decode one character at a time so only the whitespace itself is looked at
+        let c = if b.is_ascii() {
+            Some(char::from(b))
+        } else {
+            let window = &bytes[i..bytes.len().min(i + 4)];
+            window.utf8_chunks().next().and_then(|chunk| chunk.valid().chars().next())
+        };
+        match c {
+            Some(c) if c.is_whitespace() && c != char::from(sep) => i += c.len_utf8(),
+            _ => break,
+        }
+    }
+    i
 }
 
-/// Helper: check if byte can start a cookie name (alphanumeric or underscore).
+/// Find the end of a run of empty segments starting at `start`, returning the index after the
+/// run together with the number of empty segments in it.
 ///
-/// Used for heuristic detection of cookie boundaries when disambiguating
-/// whether a semicolon is a separator or part of a value.
-#[inline(always)]
-fn is_cookie_name_start(b: u8) -> bool {
-    matches!(b, b'0'..=b'9' | b'a'..=b'z' | b'A'..=b'Z' | b'_')
+/// Only segments whose separator is unambiguous are included: the separator must be followed by
+/// another separator or by trailing whitespace. Anything else is left to the lookahead.
+fn empty_run_end(bytes: &[u8], start: usize, len: usize, sep: u8) -> (usize, usize) {
+    let bytes = &bytes[..len];
+    let mut i = start;
+    let mut count = 0;
+    loop {
+        let j = skip_whitespace(bytes, i, sep);
+        if j >= len || bytes[j] != sep {
+            return (i, count);
+        }
+
+        let k = skip_whitespace(bytes, j + 1, sep);
+        if k < len && bytes[k] != sep {
+            return (i, count);
+        }
+
+        count += 1;
+        i = j + 1;
+    }
 }
 
-impl<'c, C: CookieBuilder> Iterator for HeaderStringCookies<'c, C> {
-    type Item = Result<C, ParseError>;
+/// Check if `rest` starts with a legacy RFC 2965 attribute such as `$Path=`, after optional
+/// whitespace.
+fn starts_legacy_attribute(rest: &str) -> bool {
+    let Some(rest) = rest.trim_start().strip_prefix('$') else {
+        return false;
+    };
+    let name_len =
+        rest.bytes().take_while(|&b| matches!(b, b'0'..=b'9' | b'a'..=b'z' | b'A'..=b'Z' | b'_' | b'-')).count();
+    name_len > 0 && rest[name_len..].trim_start().starts_with('=')
+}
+
+/// Check if `b` is an RFC 6265 `cookie-octet`: US-ASCII excluding controls, whitespace,
+/// double quote, comma, semicolon and backslash.
+#[inline]
+fn is_cookie_octet(b: u8) -> bool {
+    matches!(b, 0x21 | 0x23..=0x2B | 0x2D..=0x3A | 0x3C..=0x5B | 0x5D..=0x7E)
+}
+
+impl<'c, C: CookieBuilder<'c>> Iterator for HeaderStringCookies<'c, C> {
+    type Item = Result<C, HeaderParseError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let s = self.string.as_ref();
-        let len = s.len();
+        self.next_indexed().map(|(_, result)| result)
+    }
 
-        while self.last < len {
-            let i = self.last;
+    /// Every item comes from a distinct segment, so the number of semicolons left bounds the
+    /// number of items. Empty and malformed segments may be skipped, so there is no lower bound.
+    /// Once iteration from the back has started, the count is exact.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if let Some(buffered) = &self.buffered {
+            return (buffered.len(), Some(buffered.len()));
+        }
+        match self.string.get(self.last..) {
+            Some(rest) if !rest.is_empty() => {
+                let sep = self.parser.separator();
+                let commas = self.parser.folds_commas();
+                (0, Some(rest.bytes().filter(|&b| b == sep || (commas && b == b',')).count() + 1))
+            }
+            _ => (0, Some(0)),
+        }
+    }
+}
 
-            let j = s[i..].find(';').map(|k| i + k).unwrap_or(len);
-
-            // Check if this semicolon is actually a separator or part of value
-            let end_pos = if j < len {
-                // Look ahead to determine if semicolon is separator
-                let after = &s[j + 1..];
-                let trimmed = after.trim_start();
-
-                // Semicolon is separator if:
-                // 1. Followed by whitespace/semicolon only, OR
-                // 2. Followed by a valid cookie name (starts with alnum/underscore) and then '='
-                if trimmed.is_empty() || trimmed.starts_with(';') {
-                    j // Separator
-                } else if let Some(first) = trimmed.as_bytes().first().copied() {
-                    if is_cookie_name_start(first) {
-                        // Check if followed by '=' (indicating new cookie)
-                        if let Some(eq_pos) = trimmed.find('=') {
-                            let name_part = &trimmed[..eq_pos].trim();
-                            // Valid cookie name before '=' means this is a new cookie
-                            if !name_part.is_empty()
-                                && name_part.chars().all(|c| {
-                                    let b = c as u8;
-                                    matches!(b, b'0'..=b'9' | b'a'..=b'z' | b'A'..=b'Z' | b'_' | b'-')
-                                })
-                            {
-                                j // Separator - new cookie starts here
-                            } else {
-                                // Not a valid cookie, semicolon is part of value - find next real separator
-                                self.find_real_separator(j)
-                            }
-                        } else {
-                            // No '=' found, semicolon is part of value
-                            self.find_real_separator(j)
-                        }
-                    } else {
-                        // Doesn't start with valid cookie char, semicolon is part of value
-                        self.find_real_separator(j)
-                    }
-                } else {
-                    j // End of string
-                }
-            } else {
-                j // No semicolon found, end of string
-            };
+/// Iterating from the back yields exactly the items of forward iteration in reverse order.
+///
+/// The lookahead heuristics only work forward, so a semicolon can't be classified by scanning
+/// backward from it. Instead, the first call to `next_back` scans the rest of the header forward
+/// and buffers the byte ranges of each segment, which takes time and memory linear in the number
+/// of segments left. No cookie is constructed or decoded ahead of time, and calls to `next` and
+/// `next_back` can be mixed. Limits such as `max_cookies` are applied in forward order, so with
+/// `.rev()` the terminal `TooManyCookies` error comes first, followed by the first cookies of
+/// the header.
+impl<'c, C: CookieBuilder<'c>> DoubleEndedIterator for HeaderStringCookies<'c, C> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.buffer_remaining();
+        let (_, ranges, attributes, split) = self.buffered.as_mut()?.pop_back()?;
+        self.legacy_attributes = attributes;
+        self.split = split;
+
+        Some(self.build_item(ranges))
+    }
+}
 
-            self.last = end_pos + 1;
+impl<'c, C: CookieBuilder<'c>> HeaderStringCookies<'c, C> {
+    /// Advance to the next cookie, returning it together with the byte range of its raw segment.
+    fn next_indexed(&mut self) -> Option<(Range<usize>, Result<C, HeaderParseError>)> {
+        let (segment, ranges) = self.next_ranges()?;
+        Some((segment, self.build_item(ranges)))
+    }
 
-            let cookie_str = s[i..end_pos].trim();
+    /// Build the item for a scanned segment, counting it if a cookie is produced.
+    fn build_item(&mut self, ranges: CookieRanges) -> Result<C, HeaderParseError> {
+        // Decode and create cookie - borrowing from the source when possible, owned strings otherwise
+        let cookie_result = ranges.and_then(|(name, value)| self.build_cookie(name, value));
 
-            // Skip empty cookies
-            if cookie_str.is_empty() {
-                continue;
+        if cookie_result.is_ok() {
+            self.emitted += 1;
+        }
+        cookie_result
+    }
+
+    /// Scan all remaining segments ahead of time so that they can be taken from the back.
+    fn buffer_remaining(&mut self) {
+        if self.buffered.is_some() {
+            return;
+        }
+
+        // Count cookies as they're scanned so that `max_cookies` cuts off at the same point,
+        // then restore the count, since cookies are only counted once they're produced
+        let emitted = self.emitted;
+        let mut buffered = VecDeque::new();
+        while let Some((segment, ranges)) = self.scan_ranges() {
+            if ranges.is_ok() {
+                self.emitted += 1;
             }
+            buffered.push_back((segment, ranges, core::mem::take(&mut self.legacy_attributes), self.split));
+        }
 
-            // Find '=' separator
-            let eq_pos = match cookie_str.find('=') {
-                Some(p) => p,
-                None => continue,
-            };
+        self.emitted = emitted;
+        self.buffered = Some(buffered);
+    }
+
+    /// Advance to the next cookie segment, returning its byte range together with the byte
+    /// ranges of the cookie's name and raw value.
+    ///
+    /// Legacy `$` attributes are dropped or collected according to the parser's
+    /// [`LegacyAttributes`] mode. Callers must increment `emitted` for each cookie they produce
+    /// successfully.
+    fn next_ranges(&mut self) -> Option<(Range<usize>, CookieRanges)> {
+        if let Some(buffered) = &mut self.buffered {
+            let (segment, ranges, attributes, split) = buffered.pop_front()?;
+            self.legacy_attributes = attributes;
+            self.split = split;
+            return Some((segment, ranges));
+        }
+        self.scan_ranges()
+    }
+
+    /// Scan the next cookie segment, dropping or collecting legacy `$` attributes.
+    fn scan_ranges(&mut self) -> Option<(Range<usize>, CookieRanges)> {
+        let mode = self.parser.legacy_attributes;
 
-            let name = cookie_str[..eq_pos].trim();
-            let val = cookie_str[eq_pos + 1..].trim();
+        loop {
+            let (segment, ranges) = self.next_segment()?;
+            let Ok((name, _)) = &ranges else {
+                return Some((segment, ranges));
+            };
 
-            if name.is_empty() {
+            if mode != LegacyAttributes::AsCookies && self.string[name.clone()].starts_with('$') {
+                // Attributes that don't follow a cookie, such as `$Version`, have nothing to attach to
                 continue;
             }
+            if mode == LegacyAttributes::Attach {
+                self.take_legacy_attributes();
+            }
+            return Some((segment, ranges));
+        }
+    }
+
+    /// Consume the legacy `$` attributes directly following the current position, recording
+    /// their ranges for the cookie just scanned.
+    ///
+    /// Attribute segments that fail validation are dropped.
+    fn take_legacy_attributes(&mut self) {
+        self.legacy_attributes.clear();
+
+        while self.string.get(self.last..).is_some_and(starts_legacy_attribute) {
+            match self.next_segment() {
+                Some((_, Ok((name, value)))) => {
+                    let quoted = self.string[value.clone()].len() >= 2
+                        && self.string[value.clone()].starts_with('"')
+                        && self.string[value.clone()].ends_with('"');
+                    let value = if quoted {
+                        value.start + 1..value.end - 1
+                    } else {
+                        value
+                    };
+                    self.legacy_attributes.push((name, value));
+                }
+                Some((_, Err(_))) => {}
+                None => break,
+            }
+        }
+    }
+
+    /// Scan the next cookie segment, returning its byte range together with the byte ranges of
+    /// the cookie's name and raw value.
+    fn next_segment(&mut self) -> Option<(Range<usize>, CookieRanges)> {
+        let s = self.string.as_ref();
+        let len = s.len();
+
+        // Reject oversized headers up front, before any scanning work is done
+        if let Some(limit) = self.parser.max_header_len.filter(|&max| self.last == 0 && len > max) {
+            self.last = len;
+            return Some((
+                0..len,
+                Err(HeaderParseError::HeaderTooLong {
+                    limit,
+                }),
+            ));
+        }
+
+        // Skip a `Cookie:` header name copied along with the value
+        if self.last == 0 && self.parser.strip_header_name {
+            self.last = header_name_end(s);
+        }
+
+        // Strict mode splits like `SplitCookies` and reports malformed segments instead of skipping them
+        let strict = self.parser.mode == ParseMode::Strict;
+        let sep = char::from(self.parser.separator());
+        let require_space = self.parser.require_space_after_separator;
+        let len = content_len(s);
+
+        let commas = self.parser.folds_commas();
+
+        // Find the next separator candidate, which must be followed by whitespace if required.
+        // A comma only counts if it is followed by a cookie.
+        let find_sep = |mut from: usize| loop {
+            let found = if commas {
+                s[from..len].find([sep, ','])
+            } else {
+                s[from..len].find(sep)
+            };
+            let Some(k) = found else {
+                return len;
+            };
+            let pos = from + k;
+            let is_candidate = if s.as_bytes()[pos] == b',' && commas {
+                is_comma_separator(&s.as_bytes()[..len], pos, self.parser.separator(), self.parser.name_chars)
+            } else {
+                !require_space || s[pos + 1..len].chars().next().is_none_or(char::is_whitespace)
+            };
+            if is_candidate {
+                return pos;
+            }
+            from = pos + 1;
+        };
 
-            // Create cookie - using owned strings for compatibility across implementations
-            let cookie_result = if val.contains('%') {
-                #[cfg(feature = "percent-encode")]
-                {
-                    // Build the cookie string for percent-decoding
-                    let mut cookie_str_buf = String::with_capacity(name.len() + val.len() + 1);
-                    cookie_str_buf.push_str(name);
-                    cookie_str_buf.push('=');
-                    cookie_str_buf.push_str(val);
-                    C::parse_encoded(cookie_str_buf)
+        while self.last < len {
+            // Skip runs of empty segments in one pass rather than one lookahead each
+            if !require_space {
+                let (run_end, empty) = empty_run_end(s.as_bytes(), self.last, len, self.parser.separator());
+                self.last = run_end;
+                self.empty_skipped += empty;
+                if self.last >= len {
+                    break;
                 }
-                #[cfg(not(feature = "percent-encode"))]
-                {
-                    // Without percent-encode feature, treat % as literal character
-                    Ok(C::new(name.to_string(), val.to_string()))
+            }
+            let i = self.last;
+
+            // An opaque cookie's value runs up to the sentinel, so there's nothing to look ahead for
+            let opaque = self.find_opaque_end(i, len);
+            let mut j = match opaque {
+                Some((end, _)) => end,
+                None => find_sep(i),
+            };
+
+            // A double-quoted value is taken literally, so start looking for the separator after it
+            if !strict
+                && opaque.is_none()
+                && let Some(quote_end) = self.find_quoted_value_end(i, j)
+            {
+                j = find_sep(quote_end.min(len));
+            }
+
+            // Check if this semicolon is actually a separator or part of value. Strict mode
+            // treats every semicolon as a separator, like `SplitCookies`.
+            let end_pos = if opaque.is_some() || require_space {
+                // A separator known to be followed by whitespace needs no lookahead
+                j
+            } else if commas && s.as_bytes().get(j) == Some(&b',') {
+                // A comma was only found because a cookie follows it
+                j
+            } else if self.trim(i..j).is_empty() {
+                // A segment with nothing but whitespace before the separator is empty, whatever
+                // follows the separator
+                j
+            } else if j < len
+                && !strict
+                && self.parser.boundary_strategy.is_none()
+                && is_clean_separator(s.as_bytes(), j, self.parser.name_chars)
+            {
+                // Fast path: the overwhelmingly common `; name=` pattern is always a separator
+                j
+            } else if j < len && !strict {
+                // Ask the boundary strategy, and if the semicolon is part of the value, find the
+                // next real separator
+                if self.parser.is_separator(&s.as_bytes()[..len], j) {
+                    j
+                } else {
+                    self.find_real_separator(j)
                 }
             } else {
-                Ok(C::new(name.to_string(), val.to_string()))
+                j // No semicolon found or strict mode
+            };
+
+            self.split = if end_pos != j {
+                trace!(separator = j, end = end_pos, "separator kept in the value by the lookahead");
+                SplitKind::HeuristicJoin
+            } else if j < len {
+                SplitKind::CleanSeparator
+            } else {
+                SplitKind::EndOfString
+            };
+            self.last = match opaque {
+                Some((_, resume)) => resume,
+                None => end_pos + 1,
             };
 
-            return Some(cookie_result);
+            match self.read_segment(i..end_pos) {
+                SegmentRead::Cookie(ranges) => {
+                    // Exceeding the cookie limit is terminal
+                    if matches!(ranges, Err(HeaderParseError::TooManyCookies { .. })) {
+                        self.last = len;
+                    }
+                    return Some((i..end_pos, ranges));
+                }
+                SegmentRead::Skipped(SkipReason::Empty) => {
+                    trace!(start = i, end = end_pos, "skipped empty segment");
+                    self.empty_skipped += 1;
+                }
+                SegmentRead::Skipped(SkipReason::MissingEquals) => {
+                    trace!(start = i, end = end_pos, "skipped segment without `=`");
+                    self.missing_equals_skipped += 1;
+                }
+                SegmentRead::Skipped(SkipReason::EmptyName) => {
+                    trace!(start = i, end = end_pos, "skipped cookie with an empty name");
+                    self.empty_name_skipped += 1;
+                }
+            }
         }
 
         None
     }
-}
 
-impl<'c, C: CookieBuilder> HeaderStringCookies<'c, C> {
-    /// Find the real cookie separator when a semicolon appears within an unquoted value.
-    ///
-    /// This method uses heuristics to determine if a semicolon is a cookie separator
-    /// (indicating the start of a new cookie) or part of the current cookie's value.
-    /// It looks ahead for patterns that indicate a new cookie boundary.
+    /// Narrow `range` so that it excludes leading and trailing whitespace.
     #[inline]
-    fn find_real_separator(&self, start: usize) -> usize {
+    fn trim(&self, range: Range<usize>) -> Range<usize> {
+        // Pure ASCII headers, the norm, are trimmed byte by byte; others need UTF-8 decoding
+        if self.ascii {
+            trim_range_ascii(self.string.as_bytes(), range)
+        } else {
+            trim_range(&self.string, range)
+        }
+    }
+
+    /// Read the cookie in the segment at `range`, which runs between two separators, applying
+    /// the configured trimming and validation.
+    pub(crate) fn read_segment(&self, range: Range<usize>) -> SegmentRead {
         let s = self.string.as_ref();
-        let bytes = s.as_bytes();
-        let len = s.len();
-        let mut i = start + 1;
+        let strict = self.parser.mode == ParseMode::Strict;
+        let sep = char::from(self.parser.separator());
+        let (i, end_pos) = (range.start, range.end);
 
-        // Skip whitespace
-        while i < len && bytes[i].is_ascii_whitespace() {
-            i += 1;
+        let segment = self.trim(i..end_pos);
+
+        // Skip empty cookies, including runs of separators that weren't split because they
+        // aren't followed by whitespace
+        if s[segment.clone()].chars().all(|c| c == sep || c.is_whitespace()) {
+            return SegmentRead::Skipped(SkipReason::Empty);
         }
 
-        // Look for next semicolon that's a real separator
-        while i < len {
-            if bytes[i] == b';' {
-                let mut j = i + 1;
-                while j < len && bytes[j].is_ascii_whitespace() {
-                    j += 1;
-                }
+        // Reject segments that contained invalid UTF-8 when parsing bytes strictly
+        let k = self.invalid_utf8.partition_point(|(range, _)| range.end <= i);
+        if let Some((range, err)) = self.invalid_utf8.get(k)
+            && range.start < end_pos
+        {
+            return SegmentRead::Cookie(Err(HeaderParseError::InvalidUtf8(*err)));
+        }
 
-                if j >= len || bytes[j] == b';' {
-                    return i; // Real separator
+        // Find '=' separator; a bare token may be accepted as a valueless cookie
+        let mut whitespace_only = false;
+        let (name_range, mut val_range) = match s.as_bytes()[segment.clone()].iter().position(|&b| b == b'=') {
+            Some(p) => {
+                let name_range = self.trim(segment.start..segment.start + p);
+                let raw_value = segment.start + p + 1..end_pos;
+                let trimmed = self.trim(raw_value.clone());
+                whitespace_only = trimmed.is_empty() && !raw_value.is_empty();
+                let keep_whitespace = match self.parser.whitespace_values {
+                    WhitespaceValues::Preserve if whitespace_only => true,
+                    _ => self.parser.keep_value_whitespace,
+                };
+                if keep_whitespace {
+                    (name_range, raw_value)
+                } else {
+                    (name_range, trimmed)
                 }
+            }
+            None if self.parser.allow_valueless && is_token(&s[segment.clone()]) => {
+                (segment.clone(), segment.end..segment.end)
+            }
+            None if strict => return SegmentRead::Cookie(Err(ParseError::MissingPair.into())),
+            None => return SegmentRead::Skipped(SkipReason::MissingEquals),
+        };
 
-                // Check if followed by new cookie
-                if j < len && is_cookie_name_start(bytes[j]) {
-                    let mut k = j;
-                    while k < len && matches!(bytes[k], b'0'..=b'9' | b'a'..=b'z' | b'A'..=b'Z' | b'_' | b'-') {
-                        k += 1;
-                    }
-                    if k < len && bytes[k] == b'=' {
-                        return i; // Real separator - new cookie found
-                    }
-                }
+        // Strip the quotes from a fully quoted value
+        if !strict
+            && self.parser.find_closing_quote(s.as_bytes(), val_range.start) == Some(val_range.end.wrapping_sub(1))
+        {
+            val_range = val_range.start + 1..val_range.end - 1;
+        }
+        let (name, val) = match (checked_slice(s, name_range.clone()), checked_slice(s, val_range.clone())) {
+            (Ok(name), Ok(val)) => (name, val),
+            (Err(err), _) | (_, Err(err)) => return SegmentRead::Cookie(Err(err.into())),
+        };
+
+        if name.is_empty() {
+            if strict || self.parser.error_on_empty_name {
+                return SegmentRead::Cookie(Err(ParseError::EmptyName.into()));
             }
-            i += 1;
+            return SegmentRead::Skipped(SkipReason::EmptyName);
         }
 
-        len // No separator found, end of string
-    }
-}
+        // Prefixed names can't have their attribute requirements verified from a request header
+        if self.parser.reject_prefixed_names
+            && let Some(prefix) = CookiePrefix::of(name)
+        {
+            return SegmentRead::Cookie(Err(HeaderParseError::PrefixedName {
+                prefix,
+            }));
+        }
 
-pub trait CookieHeaderStringExt<'c, C: CookieBuilder> {
-    fn header_string_parse<S>(string: S) -> HeaderStringCookies<'c, C>
-    where
-        S: Into<Cow<'c, str>>;
-}
+        // Once the limit is reached, any further cookie terminates the iteration
+        if let Some(limit) = self.parser.max_cookies.filter(|&max| self.emitted >= max) {
+            return SegmentRead::Cookie(Err(HeaderParseError::TooManyCookies {
+                limit,
+            }));
+        }
 
-/// Implementation of CookieBuilder for `cookie::Cookie`
-impl CookieBuilder for Cookie<'static> {
-    fn new(name: String, value: String) -> Self {
-        Cookie::new(name, value)
-    }
+        // Reject oversized names and values before anything is copied out of the source
+        if let Some(limit) = self.parser.max_name_len.filter(|&max| name.len() > max) {
+            return SegmentRead::Cookie(Err(HeaderParseError::NameTooLong {
+                limit,
+            }));
+        }
+        if let Some(limit) = self.parser.max_value_len.filter(|&max| val.len() > max) {
+            return SegmentRead::Cookie(Err(HeaderParseError::ValueTooLong {
+                limit,
+            }));
+        }
 
-    #[cfg(feature = "percent-encode")]
-    fn parse_encoded(cookie_str: String) -> Result<Self, ParseError> {
-        Cookie::parse_encoded(cookie_str)
+        if let Some(limit) =
+            self.parser.max_value_equals.filter(|&max| val.bytes().filter(|&b| b == b'=').count() > max)
+        {
+            return SegmentRead::Cookie(Err(HeaderParseError::TooManyEquals {
+                limit,
+            }));
+        }
+
+        // Whitespace-only values may need to be told apart from explicitly empty ones
+        if whitespace_only && self.parser.whitespace_values == WhitespaceValues::Reject {
+            return SegmentRead::Cookie(Err(HeaderParseError::WhitespaceOnlyValue));
+        }
+
+        // Raw control bytes could enable header injection if the value is re-serialized
+        if self.parser.reject_control_chars
+            && let Some(byte) = val.bytes().find(|&b| b.is_ascii_control() && b != b'\t')
+        {
+            return SegmentRead::Cookie(Err(HeaderParseError::ControlCharacter {
+                byte,
+            }));
+        }
+
+        // RFC 6265 only allows `cookie-octet`s, optionally wrapped in double quotes
+        if self.parser.validate_cookie_octets {
+            let unquoted = val.strip_prefix('"').and_then(|v| v.strip_suffix('"')).unwrap_or(val);
+            if let Some(byte) = unquoted.bytes().find(|&b| !is_cookie_octet(b)) {
+                return SegmentRead::Cookie(Err(HeaderParseError::InvalidCookieOctet {
+                    byte,
+                }));
+            }
+        }
+
+        SegmentRead::Cookie(Ok((name_range, val_range)))
     }
 }
 
-impl<'c> CookieHeaderStringExt<'c, Cookie<'static>> for Cookie<'c> {
-    #[inline(always)]
-    fn header_string_parse<S>(string: S) -> HeaderStringCookies<'c, Cookie<'static>>
+impl<'c, C: CookieBuilder<'c>> HeaderStringCookies<'c, C> {
+    /// Build a cookie from the given name and value ranges of the source string, decoding the
+    /// value with the parser's [`ValueDecoder`].
+    ///
+    /// Borrows from the source when it is borrowed for `'c` and the value needs no decoding,
+    /// and allocates otherwise.
+    #[inline]
+    fn build_cookie(&self, name: Range<usize>, value: Range<usize>) -> Result<C, HeaderParseError> {
+        self.build_cookie_with(name, value, |name, value| C::new(name.into_owned(), value.into_owned()))
+    }
+
+    /// Build a cookie like [`build_cookie`](Self::build_cookie), calling `owned` to construct
+    /// it whenever the name or value can't be borrowed from the source for `'c`.
+    #[inline]
+    fn build_cookie_with<F>(&self, name: Range<usize>, value: Range<usize>, owned: F) -> Result<C, HeaderParseError>
     where
-        S: Into<Cow<'c, str>>,
+        F: FnOnce(Cow<'_, str>, Cow<'_, str>) -> C,
     {
-        HeaderStringCookies {
-            string: string.into(),
-            last: 0,
-            _phantom: std::marker::PhantomData,
+        let mut cookie = match self.string {
+            Cow::Borrowed(s) => {
+                let value = self.parser.decode(&s[name.clone()], &s[value])?;
+                match (self.parser.normalize_name(&s[name]), value) {
+                    (Cow::Borrowed(name), Cow::Borrowed(value)) => C::new_borrowed(name, value),
+                    (name, value) => owned(name, value),
+                }
+            }
+            Cow::Owned(ref s) => {
+                let value = self.parser.decode(&s[name.clone()], &s[value])?;
+                owned(self.parser.normalize_name(&s[name]), value)
+            }
+        };
+
+        for (name, value) in &self.legacy_attributes {
+            cookie.set_legacy_attribute(&self.string[name.clone()], &self.string[value.clone()]);
         }
+        Ok(cookie)
     }
-}
 
-/// Optional support for reqwest integration when `reqwest` feature is enabled.
-#[cfg(feature = "reqwest")]
-pub mod reqwest_support {
+    /// Return counts of what the iterator has produced and skipped so far.
+    ///
+    /// Read this after the iterator is exhausted (e.g. by iterating with `by_ref()`) to get
+    /// the totals for the whole header.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    /// use ri_cookie_header_string::CookieHeaderStringExt;
+    ///
+    /// let mut iter = Cookie::header_string_parse("flag;; ;a=1; b=2");
+    /// let cookies: Vec<_> = iter.by_ref().filter_map(|result| result.ok()).collect();
+    /// let stats = iter.stats();
+    ///
+    /// assert_eq!(cookies.len(), 2);
+    /// assert_eq!((stats.emitted, stats.empty_skipped, stats.missing_equals_skipped), (2, 2, 1));
+    /// ```
+    pub fn stats(&self) -> ParseStats {
+        ParseStats {
+            emitted: self.emitted,
+            empty_skipped: self.empty_skipped,
+            missing_equals_skipped: self.missing_equals_skipped,
+            empty_name_skipped: self.empty_name_skipped,
+        }
+    }
+
+    /// Return the part of the header that hasn't been consumed yet.
+    ///
+    /// This starts just past the separator that ended the last segment returned, so it is the
+    /// whole header before iteration starts and empty once the iterator is exhausted. After
+    /// iterating from the back, only the segments not yet returned from either end are
+    /// included.
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    /// use ri_cookie_header_string::CookieHeaderStringExt;
+    ///
+    /// let mut iter = Cookie::header_string_parse("a=1; b=x;y; c=3");
+    /// iter.next();
+    ///
+    /// assert_eq!(iter.remaining(), " b=x;y; c=3");
+    /// ```
+    pub fn remaining(&self) -> &str {
+        let range = match &self.buffered {
+            Some(buffered) => match (buffered.front(), buffered.back()) {
+                (Some((first, ..)), Some((last, ..))) => first.start..last.end,
+                _ => return "",
+            },
+            None => self.last..self.string.len(),
+        };
+        self.string.get(range).unwrap_or_default()
+    }
+
+    /// Return `true` if the rest of the header is only whitespace and separators, so the
+    /// iterator will yield no more items.
+    ///
+    /// This is always the case for an empty header. A `false` result doesn't guarantee an item,
+    /// since segments without a `=` may still be skipped in [`ParseMode::Lenient`].
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    /// use ri_cookie_header_string::CookieHeaderStringExt;
+    ///
+    /// assert!(Cookie::header_string_parse(" ;; ").is_empty());
+    /// assert!(!Cookie::header_string_parse("a=1").is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        let sep = char::from(self.parser.separator());
+        self.remaining().chars().all(|c| c == sep || c.is_whitespace())
+    }
+
+    /// Convert this iterator into one that also yields the byte range of each cookie.
+    ///
+    /// See [`IndexedHeaderStringCookies`] for details on the reported ranges.
+    pub fn indexed(self) -> IndexedHeaderStringCookies<'c, C> {
+        IndexedHeaderStringCookies {
+            inner: self,
+        }
+    }
+
+    /// Convert this iterator into one that also yields the byte ranges of each cookie's name and
+    /// value.
+    ///
+    /// See [`SpannedHeaderStringCookies`] for details on the reported ranges.
+    pub fn spans(self) -> SpannedHeaderStringCookies<'c, C> {
+        SpannedHeaderStringCookies {
+            inner: self,
+        }
+    }
+
+    /// Convert this iterator into one that also reports how each cookie's segment was terminated.
+    ///
+    /// See [`SplitKind`] for the possible outcomes.
+    pub fn traced(self) -> TracedHeaderStringCookies<'c, C> {
+        TracedHeaderStringCookies {
+            inner: self,
+        }
+    }
+
+    /// Convert this iterator into one that never fails, yielding a placeholder cookie in place
+    /// of each error.
+    ///
+    /// See [`LossyHeaderStringCookies`] for the placeholder that is used.
+    pub fn lossy(self) -> LossyHeaderStringCookies<'c, C> {
+        LossyHeaderStringCookies {
+            inner: self,
+            placeholder: Cow::Borrowed(LOSSY_PLACEHOLDER),
+        }
+    }
+
+    /// Convert this iterator into one that only constructs the cookies whose names pass
+    /// `predicate`.
+    ///
+    /// See [`FilteredHeaderStringCookies`].
+    pub fn filter_names<F>(self, predicate: F) -> FilteredHeaderStringCookies<'c, C, F>
+    where
+        F: FnMut(&str) -> bool,
+    {
+        FilteredHeaderStringCookies {
+            inner: self,
+            predicate,
+        }
+    }
+
+    /// Convert this iterator into one that yields only the errors encountered while parsing.
+    pub fn errors(self) -> HeaderStringErrors<'c, C> {
+        HeaderStringErrors {
+            inner: self,
+        }
+    }
+
+    /// Create an iterator over `string` driven by the given parser configuration.
+    pub(crate) fn new(string: Cow<'c, str>, parser: HeaderStringParser) -> Self {
+        HeaderStringCookies {
+            ascii: string.is_ascii(),
+            string,
+            last: 0,
+            parser,
+            emitted: 0,
+            empty_skipped: 0,
+            missing_equals_skipped: 0,
+            empty_name_skipped: 0,
+            split: SplitKind::EndOfString,
+            invalid_utf8: Vec::new(),
+            legacy_attributes: Vec::new(),
+            buffered: None,
+            _phantom: core::marker::PhantomData,
+        }
+    }
+
+    /// Create an iterator over raw header bytes driven by the given parser configuration.
+    ///
+    /// Valid UTF-8 is borrowed as is. Otherwise each invalid sequence is replaced with
+    /// `U+FFFD`, and in [`Utf8Mode::Strict`] its position is recorded so that the segment
+    /// containing it yields an error.
+    pub(crate) fn from_bytes(bytes: &'c [u8], parser: HeaderStringParser) -> Self {
+        let (string, invalid_utf8) = match core::str::from_utf8(bytes) {
+            Ok(string) => (Cow::Borrowed(string), Vec::new()),
+            Err(_) => {
+                let (string, invalid_utf8) = replace_invalid_utf8(bytes, parser.utf8_mode);
+                (Cow::Owned(string), invalid_utf8)
+            }
+        };
+
+        HeaderStringCookies {
+            invalid_utf8,
+            ..Self::new(string, parser)
+        }
+    }
+
+    /// Create an iterator over owned raw header bytes, handling invalid UTF-8 like
+    /// [`from_bytes`](Self::from_bytes).
+    #[cfg(feature = "futures")]
+    pub(crate) fn from_owned_bytes(bytes: Vec<u8>, parser: HeaderStringParser) -> Self {
+        let (string, invalid_utf8) = match String::from_utf8(bytes) {
+            Ok(string) => (string, Vec::new()),
+            Err(err) => replace_invalid_utf8(err.as_bytes(), parser.utf8_mode),
+        };
+
+        HeaderStringCookies {
+            invalid_utf8,
+            ..Self::new(Cow::Owned(string), parser)
+        }
+    }
+
+    /// If the segment starting at `start` has a double-quoted value, return the index just past
+    /// its closing quote.
+    ///
+    /// `first_semicolon` is the position of the first semicolon after `start`; the `=` must
+    /// appear before it. Unterminated quotes, and closing quotes that are not followed by the
+    /// separator or the end of the header, return `None` so the heuristics apply as usual.
+    fn find_quoted_value_end(&self, start: usize, first_semicolon: usize) -> Option<usize> {
+        let s = self.string.as_ref();
+        let sep = char::from(self.parser.separator());
+        let eq_pos = start + s[start..first_semicolon].find('=')?;
+        let value = &s[eq_pos + 1..];
+        let value_start = eq_pos + 1 + (value.len() - value.trim_start().len());
+
+        let quote_end = self.parser.find_closing_quote(s.as_bytes(), value_start)? + 1;
+        let rest = s[quote_end..].trim_start_matches(|c: char| c.is_whitespace() && c != sep);
+
+        let folded = self.parser.folds_commas()
+            && rest.starts_with(',')
+            && is_comma_separator(s.as_bytes(), s.len() - rest.len(), self.parser.separator(), self.parser.name_chars);
+        (rest.is_empty() || rest.starts_with(sep) || folded).then_some(quote_end)
+    }
+
+    /// If the segment starting at `start` is a cookie with an opaque name, return the end of its
+    /// value and the index where parsing resumes after the sentinel.
+    fn find_opaque_end(&self, start: usize, len: usize) -> Option<(usize, usize)> {
+        let names = self.parser.opaque_names.as_ref()?;
+        let s = self.string.as_ref();
+        let sep = char::from(self.parser.separator());
+
+        // Only look as far as the first separator, so the name can't span segments
+        let name_end = start + s[start..len].find(['=', sep])?;
+        let name = s[start..name_end].trim();
+        if !s[name_end..].starts_with('=') || !names.iter().any(|opaque| opaque == name) {
+            return None;
+        }
+
+        let value_start = name_end + 1;
+        match self.parser.opaque_sentinel.as_deref().filter(|sentinel| !sentinel.is_empty()) {
+            Some(sentinel) => match s[value_start..len].find(sentinel) {
+                Some(k) => Some((value_start + k, value_start + k + sentinel.len())),
+                None => Some((len, len)),
+            },
+            None => Some((len, len)),
+        }
+    }
+
+    /// Find the real cookie separator when a semicolon appears within an unquoted value.
+    ///
+    /// Each later semicolon is checked with the parser's [`BoundaryStrategy`] to determine if
+    /// it is a cookie separator (indicating the start of a new cookie) or part of the current
+    /// cookie's value, skipping over double-quoted regions of the value.
+    #[inline]
+    fn find_real_separator(&self, start: usize) -> usize {
+        let s = self.string.as_ref();
+        let len = content_len(s);
+        let bytes = &s.as_bytes()[..len];
+        let sep = self.parser.separator();
+        let scan_end = self.parser.max_separator_scan.map_or(len, |max| len.min(start.saturating_add(max)));
+        let mut i = start + 1;
+
+        // Look for next semicolon that's a real separator
+        while i < len {
+            if i > scan_end {
+                trace!(start, "separator scan limit reached");
+                return start;
+            }
+
+            // Skip over quoted regions so a `key=` inside quotes isn't mistaken for a new cookie
+            if let Some(quote_end) = self.parser.find_closing_quote(bytes, i) {
+                i = quote_end + 1;
+                continue;
+            }
+
+            let is_real = match bytes[i] {
+                b if b == sep => self.parser.is_separator(bytes, i),
+                b',' => self.parser.folds_commas() && is_comma_separator(bytes, i, sep, self.parser.name_chars),
+                _ => false,
+            };
+            if is_real {
+                trace!(start, separator = i, "found the real separator");
+                return i;
+            }
+            i += 1;
+        }
+
+        trace!(start, "no real separator before the end of the header");
+        len // No separator found, end of string
+    }
+}
+
+/// Iterator over cookies in a header string paired with their location in the source.
+///
+/// Each item is the byte range of the raw `name=value` segment in the original string,
+/// together with the parse result for that segment. The range covers the segment as it
+/// appears between separators, before whitespace trimming, so it can be used to map both
+/// cookies and errors back to the input for logging or redaction. Ranges are shifted by the
+/// parser's [`base_offset`](HeaderStringParserBuilder::base_offset).
+///
+/// Created by [`HeaderStringCookies::indexed`] or
+/// [`CookieHeaderStringExt::header_string_parse_indexed`].
+pub struct IndexedHeaderStringCookies<'c, C: CookieBuilder<'c>> {
+    inner: HeaderStringCookies<'c, C>,
+}
+
+impl<'c, C: CookieBuilder<'c>> Iterator for IndexedHeaderStringCookies<'c, C> {
+    type Item = (Range<usize>, Result<C, HeaderParseError>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (segment, result) = self.inner.next_indexed()?;
+        Some((self.inner.parser.offset(segment), result))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// Iterator over cookies in a header string paired with the locations of their name and value.
+///
+/// Each cookie is yielded together with the byte ranges of its name and of its raw value in the
+/// parsed string, after whitespace trimming and without the quotes of a double-quoted value.
+/// This allows masking just the value of a secret cookie in logs while keeping its name, e.g.
+/// by replacing the value range of the original header. Errors carry no ranges. Ranges are
+/// shifted by the parser's [`base_offset`](HeaderStringParserBuilder::base_offset).
+///
+/// Created by [`HeaderStringCookies::spans`] or [`CookieHeaderStringExt::header_string_parse_spans`].
+pub struct SpannedHeaderStringCookies<'c, C: CookieBuilder<'c>> {
+    inner: HeaderStringCookies<'c, C>,
+}
+
+impl<'c, C: CookieBuilder<'c>> Iterator for SpannedHeaderStringCookies<'c, C> {
+    type Item = Result<(Range<usize>, Range<usize>, C), HeaderParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, ranges) = self.inner.next_ranges()?;
+
+        Some(ranges.and_then(|(name, value)| {
+            let cookie = self.inner.build_cookie(name.clone(), value.clone())?;
+            self.inner.emitted += 1;
+            Ok((self.inner.parser.offset(name), self.inner.parser.offset(value), cookie))
+        }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// Iterator over cookies in a header string paired with their raw, undecoded values.
+///
+/// Each cookie is decoded as usual by the parser's [`ValueDecoder`], and is yielded together
+/// with its value exactly as it appears in the header, minus the quotes of a double-quoted
+/// value. This allows verifying e.g. an HMAC computed over the encoded wire form while using
+/// the decoded value for application logic.
+///
+/// Created by [`HeaderStringParser::parse_with_raw`] or
+/// [`CookieHeaderStringExt::header_string_parse_with_raw`].
+pub struct RawValueHeaderStringCookies<'c, C: CookieBuilder<'c>> {
+    source: &'c str,
+    inner: HeaderStringCookies<'c, C>,
+}
+
+impl<'c, C: CookieBuilder<'c>> RawValueHeaderStringCookies<'c, C> {
+    pub(crate) fn new(source: &'c str, parser: HeaderStringParser) -> Self {
+        RawValueHeaderStringCookies {
+            source,
+            inner: HeaderStringCookies::new(Cow::Borrowed(source), parser),
+        }
+    }
+}
+
+impl<'c, C: CookieBuilder<'c>> Iterator for RawValueHeaderStringCookies<'c, C> {
+    type Item = Result<(C, &'c str), HeaderParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, ranges) = self.inner.next_ranges()?;
+
+        Some(ranges.and_then(|(name, value)| {
+            let cookie = self.inner.build_cookie(name, value.clone())?;
+            self.inner.emitted += 1;
+            Ok((cookie, &self.source[value]))
+        }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// Counts of the segments a [`HeaderStringCookies`] iterator has produced and skipped.
+///
+/// Lenient parsing silently skips malformed segments; a flood of them can indicate a broken
+/// or malicious client. Returned by [`HeaderStringCookies::stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub struct ParseStats {
+    /// The number of cookies produced successfully.
+    pub emitted: usize,
+    /// The number of empty or whitespace-only segments skipped, e.g. from `;;`.
+    pub empty_skipped: usize,
+    /// The number of segments without a `=` skipped in lenient mode.
+    pub missing_equals_skipped: usize,
+    /// The number of segments with an empty name skipped in lenient mode.
+    pub empty_name_skipped: usize,
+}
+
+/// How the segment of a parsed cookie was terminated.
+///
+/// Useful for measuring how often the lenient heuristics are needed on real traffic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SplitKind {
+    /// The segment ended at a semicolon that was immediately recognized as a separator.
+    CleanSeparator,
+    /// One or more semicolons were judged to be part of the value, so the segment was joined
+    /// across them by the lookahead heuristics.
+    HeuristicJoin,
+    /// The segment ended at the end of the string.
+    EndOfString,
+}
+
+/// Iterator over cookies in a header string paired with how each segment was split.
+///
+/// Created by [`HeaderStringCookies::traced`] or [`CookieHeaderStringExt::header_string_parse_traced`].
+pub struct TracedHeaderStringCookies<'c, C: CookieBuilder<'c>> {
+    inner: HeaderStringCookies<'c, C>,
+}
+
+impl<'c, C: CookieBuilder<'c>> Iterator for TracedHeaderStringCookies<'c, C> {
+    type Item = (SplitKind, Result<C, HeaderParseError>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, result) = self.inner.next_indexed()?;
+        Some((self.inner.split, result))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// The value given to placeholder cookies by [`LossyHeaderStringCookies`] unless another one
+/// is configured.
+pub const LOSSY_PLACEHOLDER: &str = "<invalid>";
+
+/// Iterator over cookies in a header string that substitutes a placeholder cookie for every
+/// segment that fails to parse.
+///
+/// The placeholder is named after the text before the `=` of the failed segment, or after the
+/// whole segment if it has none, and its value is [`LOSSY_PLACEHOLDER`] unless changed with
+/// [`placeholder`](Self::placeholder). Nothing is dropped, so the cookies correspond one to one
+/// with the items of the fallible iterator, which is useful for displaying a header as parsed.
+///
+/// Created by [`HeaderStringCookies::lossy`].
+///
+/// # Example
+///
+/// ```
+/// use cookie::Cookie;
+/// use ri_cookie_header_string::{HeaderStringParser, ParseMode};
+///
+/// let parser = HeaderStringParser::builder().mode(ParseMode::Strict).build();
+/// let cookies: Vec<Cookie> = parser.parse("a=1; broken; b=2").lossy().placeholder("?").collect();
+/// let pairs: Vec<_> = cookies.iter().map(|c| c.name_value()).collect();
+///
+/// assert_eq!(pairs, [("a", "1"), ("broken", "?"), ("b", "2")]);
+/// ```
+pub struct LossyHeaderStringCookies<'c, C: CookieBuilder<'c>> {
+    inner: HeaderStringCookies<'c, C>,
+    placeholder: Cow<'c, str>,
+}
+
+impl<'c, C: CookieBuilder<'c>> LossyHeaderStringCookies<'c, C> {
+    /// Set the value given to placeholder cookies.
+    ///
+    /// Defaults to [`LOSSY_PLACEHOLDER`].
+    pub fn placeholder<S>(mut self, value: S) -> Self
+    where
+        S: Into<Cow<'c, str>>,
+    {
+        self.placeholder = value.into();
+        self
+    }
+}
+
+impl<'c, C: CookieBuilder<'c>> Iterator for LossyHeaderStringCookies<'c, C> {
+    type Item = C;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (range, result) = self.inner.next_indexed()?;
+
+        Some(result.unwrap_or_else(|_| {
+            let segment = &self.inner.string[range];
+            let name = segment.split_once('=').map_or(segment, |(name, _)| name).trim();
+            C::new(name.to_string(), self.placeholder.to_string())
+        }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// Iterator over the cookies in a header string whose names pass a predicate.
+///
+/// The predicate is called with each cookie's name as it appears in the header, before its
+/// value is decoded or anything is allocated, and cookies it rejects are skipped without being
+/// constructed. This makes picking a few allowlisted cookies out of a large header cheap.
+/// Errors are yielded as usual. Rejected cookies don't count towards
+/// [`max_cookies`](HeaderStringParserBuilder::max_cookies), but once the limit is reached any
+/// further cookie ends the iteration with an error, whether the predicate would accept it or
+/// not.
+///
+/// Created by [`HeaderStringCookies::filter_names`] or
+/// [`CookieHeaderStringExt::header_string_parse_filter`].
+pub struct FilteredHeaderStringCookies<'c, C: CookieBuilder<'c>, F> {
+    inner: HeaderStringCookies<'c, C>,
+    predicate: F,
+}
+
+impl<'c, C, F> Iterator for FilteredHeaderStringCookies<'c, C, F>
+where
+    C: CookieBuilder<'c>,
+    F: FnMut(&str) -> bool,
+{
+    type Item = Result<C, HeaderParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (_, ranges) = self.inner.next_ranges()?;
+            if let Ok((name, _)) = &ranges
+                && !(self.predicate)(&self.inner.string[name.clone()])
+            {
+                continue;
+            }
+            return Some(self.inner.build_item(ranges));
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.inner.size_hint().1)
+    }
+}
+
+/// Iterator over the errors encountered while parsing a header string.
+///
+/// Successfully parsed cookies are skipped. Created by [`HeaderStringCookies::errors`] or
+/// [`CookieHeaderStringExt::header_string_parse_errors`].
+pub struct HeaderStringErrors<'c, C: CookieBuilder<'c>> {
+    inner: HeaderStringCookies<'c, C>,
+}
+
+impl<'c, C: CookieBuilder<'c>> Iterator for HeaderStringErrors<'c, C> {
+    type Item = HeaderParseError;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.by_ref().find_map(|result| result.err())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+pub trait CookieHeaderStringExt<'c, C: CookieBuilder<'c>> {
+    fn header_string_parse<S>(string: S) -> HeaderStringCookies<'c, C>
+    where
+        S: Into<Cow<'c, str>>;
+
+    /// Parse an owned cookie header string into cookies that don't borrow from anything.
+    ///
+    /// The iterator takes ownership of `string`, so the produced cookies are `'static` and can
+    /// outlive the original header. This is equivalent to passing a `String` to
+    /// [`header_string_parse`](Self::header_string_parse), but makes the intent explicit and
+    /// avoids accidentally borrowing a temporary.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    /// use ri_cookie_header_string::CookieHeaderStringExt;
+    ///
+    /// fn session_cookies(header: String) -> Vec<Cookie<'static>> {
+    ///     Cookie::header_string_parse_owned(header).filter_map(|result| result.ok()).collect()
+    /// }
+    ///
+    /// let cookies = session_cookies(String::from("session=abc;123; other=value"));
+    /// assert_eq!(cookies[0].value(), "abc;123");
+    /// ```
+    fn header_string_parse_owned(string: String) -> HeaderStringCookies<'static, Self>
+    where
+        Self: CookieBuilder<'static>,
+    {
+        HeaderStringParser::new().parse::<Self, _>(string)
+    }
+
+    /// Parse a raw cookie header that may not be valid UTF-8.
+    ///
+    /// Headers arrive as bytes, and real-world clients occasionally send bytes that are not
+    /// valid UTF-8. Rather than rejecting the whole header, each invalid sequence is replaced
+    /// with `U+FFFD`. Use [`HeaderStringParserBuilder::utf8_mode`] with [`Utf8Mode::Strict`] to
+    /// report the affected cookies as errors instead. Valid input is borrowed without copying.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    /// use ri_cookie_header_string::CookieHeaderStringExt;
+    ///
+    /// let cookies: Vec<_> =
+    ///     Cookie::header_string_parse_bytes(b"a=1; b=\xff").filter_map(|result| result.ok()).collect();
+    ///
+    /// assert_eq!(cookies[1].value(), "\u{FFFD}");
+    /// ```
+    fn header_string_parse_bytes(bytes: &'c [u8]) -> HeaderStringCookies<'c, C> {
+        HeaderStringParser::new().parse_bytes(bytes)
+    }
+
+    /// Parse a cookie header string, yielding each result with the byte range of its raw segment.
+    fn header_string_parse_indexed<S>(string: S) -> IndexedHeaderStringCookies<'c, C>
+    where
+        S: Into<Cow<'c, str>>,
+    {
+        Self::header_string_parse(string).indexed()
+    }
+
+    /// Parse a cookie header string, yielding each cookie with the byte ranges of its name and
+    /// value.
+    ///
+    /// See [`SpannedHeaderStringCookies`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    /// use ri_cookie_header_string::CookieHeaderStringExt;
+    ///
+    /// let header = "theme=dark; session=s3cr3t";
+    /// let mut masked = header.to_string();
+    /// let spans: Vec<_> = Cookie::header_string_parse_spans(header).flatten().collect();
+    /// // Replace from the back so that earlier ranges stay valid
+    /// for (_, value, cookie) in spans.into_iter().rev() {
+    ///     if cookie.name() == "session" {
+    ///         masked.replace_range(value, "***");
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(masked, "theme=dark; session=***");
+    /// ```
+    fn header_string_parse_spans<S>(string: S) -> SpannedHeaderStringCookies<'c, C>
+    where
+        S: Into<Cow<'c, str>>,
+    {
+        Self::header_string_parse(string).spans()
+    }
+
+    /// Parse a cookie header string, only constructing the cookies whose names pass
+    /// `predicate`.
+    ///
+    /// See [`FilteredHeaderStringCookies`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    /// use ri_cookie_header_string::CookieHeaderStringExt;
+    ///
+    /// let allowed = ["session", "theme"];
+    /// let cookies: Vec<Cookie> = Cookie::header_string_parse_filter("_ga=1; session=abc;123; _gid=2", |name| {
+    ///     allowed.contains(&name)
+    /// })
+    /// .flatten()
+    /// .collect();
+    ///
+    /// assert_eq!(cookies.len(), 1);
+    /// assert_eq!(cookies[0].value(), "abc;123");
+    /// ```
+    fn header_string_parse_filter<S, F>(string: S, predicate: F) -> FilteredHeaderStringCookies<'c, C, F>
+    where
+        S: Into<Cow<'c, str>>,
+        F: FnMut(&str) -> bool,
+    {
+        Self::header_string_parse(string).filter_names(predicate)
+    }
+
+    /// Parse a cookie header string, yielding each cookie together with its raw, undecoded value.
+    ///
+    /// See [`RawValueHeaderStringCookies`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    /// use ri_cookie_header_string::CookieHeaderStringExt;
+    ///
+    /// let (cookie, raw) = Cookie::header_string_parse_with_raw("token=a%2Bb").next().unwrap().unwrap();
+    ///
+    /// assert_eq!(raw, "a%2Bb");
+    /// # #[cfg(feature = "percent-encode")]
+    /// assert_eq!(cookie.value(), "a+b");
+    /// ```
+    fn header_string_parse_with_raw(string: &'c str) -> RawValueHeaderStringCookies<'c, C> {
+        HeaderStringParser::new().parse_with_raw(string)
+    }
+
+    /// Parse a cookie header string, yielding each result with the [`SplitKind`] that ended its segment.
+    fn header_string_parse_traced<S>(string: S) -> TracedHeaderStringCookies<'c, C>
+    where
+        S: Into<Cow<'c, str>>,
+    {
+        Self::header_string_parse(string).traced()
+    }
+
+    /// Parse a cookie header string, yielding only the errors encountered.
+    fn header_string_parse_errors<S>(string: S) -> HeaderStringErrors<'c, C>
+    where
+        S: Into<Cow<'c, str>>,
+    {
+        Self::header_string_parse(string).errors()
+    }
+
+    /// Parse a cookie header string into a map of cookie names to values.
+    ///
+    /// Cookies that fail to parse are skipped. See [`HeaderStringCookies::collect_map`].
+    #[cfg(feature = "std")]
+    fn header_string_parse_map<S>(string: S, policy: DuplicatePolicy) -> HashMap<String, String>
+    where
+        S: Into<Cow<'c, str>>,
+        C: NameValue,
+    {
+        Self::header_string_parse(string).collect_map(policy)
+    }
+
+    /// Parse a cookie header string into an insertion-ordered map of cookie names to values.
+    ///
+    /// Cookies that fail to parse are skipped. See [`HeaderStringCookies::collect_indexmap`].
+    #[cfg(feature = "indexmap")]
+    fn header_string_parse_indexmap<S>(string: S, policy: DuplicatePolicy) -> indexmap::IndexMap<String, String>
+    where
+        S: Into<Cow<'c, str>>,
+        C: NameValue,
+    {
+        Self::header_string_parse(string).collect_indexmap(policy)
+    }
+
+    /// Parse a cookie header string to completion, returning every result together with the
+    /// final [`ParseStats`].
+    fn header_string_parse_stats<S>(string: S) -> (Vec<Result<C, HeaderParseError>>, ParseStats)
+    where
+        S: Into<Cow<'c, str>>,
+    {
+        let mut iter = Self::header_string_parse(string);
+        let results = iter.by_ref().collect();
+        (results, iter.stats())
+    }
+
+    /// Parse a cookie header string into an owned, ordered [`ParsedCookies`] collection.
+    ///
+    /// Cookies that fail to parse are skipped. See [`HeaderStringCookies::collect_parsed`].
+    fn header_string_parse_collect<S>(string: S) -> ParsedCookies
+    where
+        S: Into<Cow<'c, str>>,
+        C: NameValue,
+    {
+        Self::header_string_parse(string).collect_parsed()
+    }
+
+    /// Parse a cookie header string into cookies sorted by name.
+    ///
+    /// Cookies that fail to parse are skipped. See [`HeaderStringCookies::collect_sorted`].
+    fn header_string_parse_sorted<S>(string: S, order: NameOrder) -> Vec<C>
+    where
+        S: Into<Cow<'c, str>>,
+        C: NameValue,
+    {
+        Self::header_string_parse(string).collect_sorted(order)
+    }
+}
+
+/// Implementation of CookieBuilder for `cookie::Cookie`
+impl<'c> CookieBuilder<'c> for Cookie<'c> {
+    fn new(name: String, value: String) -> Self {
+        Cookie::new(name, value)
+    }
+
+    fn new_borrowed(name: &'c str, value: &'c str) -> Self {
+        Cookie::new(name, value)
+    }
+
+    fn set_legacy_attribute(&mut self, name: &str, value: &str) {
+        if name.eq_ignore_ascii_case("$Path") {
+            self.set_path(value.to_string());
+        } else if name.eq_ignore_ascii_case("$Domain") {
+            self.set_domain(value.to_string());
+        }
+    }
+}
+
+impl NameValue for Cookie<'_> {
+    fn name_value(&self) -> (&str, &str) {
+        Cookie::name_value(self)
+    }
+}
+
+impl<'c> CookieHeaderStringExt<'c, Cookie<'c>> for Cookie<'c> {
+    #[inline(always)]
+    fn header_string_parse<S>(string: S) -> HeaderStringCookies<'c, Cookie<'c>>
+    where
+        S: Into<Cow<'c, str>>,
+    {
+        HeaderStringParser::new().parse(string)
+    }
+}
+
+/// Optional support for reqwest integration when `reqwest` feature is enabled.
+#[cfg(feature = "reqwest")]
+pub mod reqwest_support {
+    use super::*;
+
+    /// Parse a cookie header string into cookies compatible with reqwest.
+    ///
+    /// This function parses HTTP Cookie header strings into `cookie::Cookie` objects
+    /// that can be used with reqwest. Since `reqwest::cookie::Cookie` is a read-only
+    /// wrapper, we work with the underlying `cookie::Cookie` type.
+    ///
+    /// # Example
+    ///
+    /// ```text
+    /// use ri_cookie_header_string::reqwest_support::parse_for_reqwest;
+    /// use url::Url;
+    ///
+    /// let cookie_header = "session=abc123; user=john";
+    /// let cookies: Vec<_> = parse_for_reqwest(cookie_header)
+    ///     .filter_map(|result| result.ok())
+    ///     .collect();
+    ///
+    /// // Use with reqwest cookie jar
+    /// let jar = reqwest::cookie::Jar::default();
+    /// let url: Url = "https://example.com".parse().unwrap();
+    /// for cookie in cookies {
+    ///     // Cookies can be serialized and added to jar
+    ///     jar.add_cookie_str(&cookie.to_string(), &url);
+    /// }
+    /// ```
+    pub fn parse_for_reqwest<'c, S>(string: S) -> HeaderStringCookies<'c, Cookie<'c>>
+    where
+        S: Into<Cow<'c, str>>,
+    {
+        HeaderStringParser::new().parse(string)
+    }
+
+    /// Parse a cookie header string and add every successfully parsed cookie to a reqwest jar.
+    ///
+    /// Each cookie is serialized with its `to_string()` form and added for `url`. Returns the
+    /// number of cookies added together with the errors for the cookies that failed to parse.
+    ///
+    /// Note that the jar parses the serialized cookie as a `Set-Cookie` value, so a value that
+    /// contains a semicolon is cut at the semicolon when stored.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ri_cookie_header_string::reqwest_support::populate_jar;
+    ///
+    /// let jar = reqwest::cookie::Jar::default();
+    /// let url: reqwest::Url = "https://example.com".parse().unwrap();
+    ///
+    /// let (added, errors) = populate_jar(&jar, &url, "session=abc123; user=john");
+    ///
+    /// assert_eq!(added, 2);
+    /// assert!(errors.is_empty());
+    /// ```
+    pub fn populate_jar(
+        jar: &reqwest::cookie::Jar,
+        url: &reqwest::Url,
+        header: &str,
+    ) -> (usize, Vec<HeaderParseError>) {
+        let mut added = 0;
+        let mut errors = Vec::new();
+
+        for result in parse_for_reqwest(header) {
+            match result {
+                Ok(cookie) => {
+                    jar.add_cookie_str(&cookie.to_string(), url);
+                    added += 1;
+                }
+                Err(err) => errors.push(err),
+            }
+        }
+
+        (added, errors)
+    }
+}
+
+/// Optional support for the `http` crate when the `http` feature is enabled.
+#[cfg(feature = "http")]
+pub mod http_support {
+    use super::*;
+
+    /// Parse the cookies in an [`http::HeaderValue`], such as a request's `Cookie` header.
+    ///
+    /// Visible-ASCII values are parsed as strings. Values with other bytes, which
+    /// [`HeaderValue::to_str`](http::HeaderValue::to_str) rejects, are routed through
+    /// [`header_string_parse_bytes`](CookieHeaderStringExt::header_string_parse_bytes) instead,
+    /// so non-UTF-8 bytes are replaced rather than failing the whole header.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use http::HeaderValue;
+    /// use ri_cookie_header_string::http_support::parse_header_value;
+    ///
+    /// let value = HeaderValue::from_static("session=abc;123; user=john");
+    /// let cookies: Vec<_> = parse_header_value(&value).filter_map(|result| result.ok()).collect();
+    ///
+    /// assert_eq!(cookies[0].value(), "abc;123");
+    /// assert_eq!(cookies[1].value(), "john");
+    /// ```
+    pub fn parse_header_value(value: &http::HeaderValue) -> HeaderStringCookies<'_, Cookie<'_>> {
+        match value.to_str() {
+            Ok(string) => HeaderStringParser::new().parse(string),
+            Err(_) => HeaderStringParser::new().parse_bytes(value.as_bytes()),
+        }
+    }
+
+    /// Rewrite a `Cookie` header value so that standard parsers read the same cookies as this
+    /// crate.
+    ///
+    /// The value is parsed with [`parse_header_value`], then the cookies are serialized back
+    /// with every value percent-encoded as by
+    /// [`encode_on_serialize`](crate::HeaderStringParserBuilder::encode_on_serialize), so a `;`
+    /// kept in a value becomes `%3B`. Segments that fail to parse and cookies whose name is not
+    /// an RFC 6265 token are dropped. The result only splits at `; `, which lets typed-header
+    /// parsers such as `headers::Cookie`, behind axum's `TypedHeader`, read the cookies this
+    /// crate found. Values read from the rewritten header are percent-encoded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use http::HeaderValue;
+    /// use ri_cookie_header_string::http_support::canonical_header_value;
+    ///
+    /// let value = HeaderValue::from_static("session=abc;123; user=john");
+    ///
+    /// assert_eq!(canonical_header_value(&value), "session=abc%3B123; user=john");
+    /// ```
+    pub fn canonical_header_value(value: &http::HeaderValue) -> http::HeaderValue {
+        canonical_value(parse_header_value(value).flatten())
+    }
+
+    /// Serialize `cookies` into a header value that only splits at `; `, dropping the cookies
+    /// whose name is not a token.
+    pub(crate) fn canonical_value<'a>(cookies: impl Iterator<Item = Cookie<'a>>) -> http::HeaderValue {
+        let header = HeaderStringParser::builder().encode_on_serialize(true).build().to_header_string(cookies);
+
+        // Names are tokens and values are encoded down to visible ASCII
+        http::HeaderValue::try_from(header).expect("serialized cookies are a valid header value")
+    }
+}
+
+/// Optional support for the [`headers`] crate's typed `Cookie` header, as used by axum's
+/// `TypedHeader`, when the `headers` feature is enabled.
+#[cfg(feature = "headers")]
+pub mod headers_support {
+    use super::*;
+    use headers::Header;
+
+    /// Parse a cookie header string into a [`headers::Cookie`] using this crate's heuristics.
+    ///
+    /// `headers::Cookie` splits its value at every semicolon, so on its own it cuts a value like
+    /// `abc;123` short and reads the rest as a separate cookie. Here the header is parsed with
+    /// the default [`HeaderStringParser`] first and handed over as by
+    /// [`canonical_header_value`](crate::http_support::canonical_header_value): values are
+    /// percent-encoded, so a `;` kept in a value reads as `%3B`, and segments that fail to parse
+    /// or whose name is not a token are dropped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ri_cookie_header_string::headers_support::parse_typed_cookie;
+    ///
+    /// let cookie = parse_typed_cookie("session=abc;123; theme=dark");
+    ///
+    /// assert_eq!(cookie.get("session"), Some("abc%3B123"));
+    /// assert_eq!(cookie.get("theme"), Some("dark"));
+    /// assert_eq!(cookie.len(), 2);
+    /// ```
+    pub fn parse_typed_cookie(header: &str) -> headers::Cookie {
+        typed_cookie_from(HeaderStringParser::new().parse(header))
+    }
+
+    /// Build a [`headers::Cookie`] from the `Cookie` headers in `headers`, or return `None` if
+    /// there are none.
+    ///
+    /// This is the replacement for axum's `TypedHeader<headers::Cookie>` extractor: take the
+    /// request's `HeaderMap` in the handler and convert it here. Each header value is parsed
+    /// with [`parse_header_value`](crate::http_support::parse_header_value), and the cookies of
+    /// several `Cookie` headers, as sent over HTTP/2, are merged in order. See
+    /// [`parse_typed_cookie`] for how the cookies are handed over.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use http::{HeaderMap, HeaderValue, header::COOKIE};
+    /// use ri_cookie_header_string::headers_support::typed_cookie;
+    ///
+    /// let mut headers = HeaderMap::new();
+    /// assert!(typed_cookie(&headers).is_none());
+    ///
+    /// headers.append(COOKIE, HeaderValue::from_static("session=abc;123"));
+    /// headers.append(COOKIE, HeaderValue::from_static("theme=dark"));
+    /// let cookie = typed_cookie(&headers).unwrap();
+    ///
+    /// assert_eq!(cookie.get("session"), Some("abc%3B123"));
+    /// assert_eq!(cookie.get("theme"), Some("dark"));
+    /// ```
+    pub fn typed_cookie(headers: &http::HeaderMap) -> Option<headers::Cookie> {
+        let mut values = headers.get_all(http::header::COOKIE).iter().peekable();
+        values.peek()?;
+        Some(typed_cookie_from(values.flat_map(http_support::parse_header_value)))
+    }
+
+    /// Hand the successfully parsed cookies over to a [`headers::Cookie`].
+    fn typed_cookie_from<'a>(results: impl Iterator<Item = Result<Cookie<'a>, HeaderParseError>>) -> headers::Cookie {
+        let value = http_support::canonical_value(results.flatten());
+        headers::Cookie::decode(&mut core::iter::once(&value)).expect("headers::Cookie accepts any header value")
+    }
+}
+
+/// Optional JSON entry point for WebAssembly builds when the `wasm` feature is enabled.
+#[cfg(feature = "wasm")]
+pub mod wasm_support {
     use super::*;
+    use serde::Serialize;
 
-    /// Parse a cookie header string into cookies compatible with reqwest.
+    /// The JSON document produced by [`parse_to_json`].
+    #[derive(Serialize)]
+    struct ParseOutput {
+        cookies: Vec<(String, String)>,
+        errors: Vec<String>,
+    }
+
+    /// Parse a cookie header string with the default configuration into a JSON document.
     ///
-    /// This function parses HTTP Cookie header strings into `cookie::Cookie` objects
-    /// that can be used with reqwest. Since `reqwest::cookie::Cookie` is a read-only
-    /// wrapper, we work with the underlying `cookie::Cookie` type.
+    /// The document is an object with a `cookies` list of `[name, value]` pairs, in the same
+    /// format as a serialized [`ParsedCookies`], and an `errors` list with the message of each
+    /// error. Only strings cross the boundary, so the function can be exported directly, e.g.
+    /// with `#[wasm_bindgen]`, without exposing `cookie::Cookie` to JavaScript.
     ///
     /// # Example
     ///
-    /// ```text
-    /// use ri_cookie_header_string::reqwest_support::parse_for_reqwest;
-    /// use url::Url;
-    ///
-    /// let cookie_header = "session=abc123; user=john";
-    /// let cookies: Vec<_> = parse_for_reqwest(cookie_header)
-    ///     .filter_map(|result| result.ok())
-    ///     .collect();
+    /// ```
+    /// use ri_cookie_header_string::wasm_support::parse_to_json;
     ///
-    /// // Use with reqwest cookie jar
-    /// let jar = reqwest::cookie::Jar::default();
-    /// let url: Url = "https://example.com".parse().unwrap();
-    /// for cookie in cookies {
-    ///     // Cookies can be serialized and added to jar
-    ///     jar.add_cookie_str(&cookie.to_string(), &url);
-    /// }
+    /// assert_eq!(
+    ///     parse_to_json("session=abc;123; =oops; theme=dark"),
+    ///     r#"{"cookies":[["session","abc;123; =oops"],["theme","dark"]],"errors":[]}"#
+    /// );
     /// ```
-    pub fn parse_for_reqwest<'c, S>(string: S) -> HeaderStringCookies<'c, Cookie<'static>>
-    where
-        S: Into<Cow<'c, str>>,
-    {
-        HeaderStringCookies {
-            string: string.into(),
-            last: 0,
-            _phantom: std::marker::PhantomData,
-        }
+    pub fn parse_to_json(header: &str) -> String {
+        let (cookies, errors) = Cookie::header_string_parse(header).partition_results();
+        let output = ParseOutput {
+            cookies: cookies.iter().map(|c| (c.name().to_string(), c.value().to_string())).collect(),
+            errors: errors.iter().map(ToString::to_string).collect(),
+        };
+
+        // Only strings are serialized, which can't fail
+        serde_json::to_string(&output).expect("serializing strings never fails")
     }
 }
 
@@ -486,6 +2178,194 @@ mod tests {
         assert_eq!(cookies[0].name(), "session-id");
     }
 
+    #[test]
+    fn header_string_parse_unicode() {
+        let cases = [
+            ("café=crème; a=🍪", vec![("café", "crème"), ("a", "🍪")]),
+            ("a=🍪;🍪; b=2", vec![("a", "🍪;🍪"), ("b", "2")]),
+            // Non-ASCII names only start a new cookie at the start of the header
+            ("名前=値; a=é=è", vec![("名前", "値"), ("a", "é=è")]),
+            ("a=é=è; 名前=値", vec![("a", "é=è; 名前=値")]),
+            // `ű` is U+0171, whose low byte is `q`; it must not pass as an ASCII name character
+            ("a=x; yű=2", vec![("a", "x; yű=2")]),
+        ];
+
+        for (string, expected) in cases {
+            let cookies: Vec<_> = Cookie::header_string_parse(string).filter_map(|parse| parse.ok()).collect();
+
+            let actual: Vec<_> = cookies.iter().map(|c| c.name_value()).collect();
+
+            assert_eq!(expected, actual, "{string}");
+        }
+    }
+
+    #[test]
+    fn checked_slice_boundaries() {
+        let s = "a=é";
+
+        assert_eq!(checked_slice(s, 2..4), Ok("é"));
+        assert!(matches!(checked_slice(s, 2..3), Err(ParseError::Utf8Error(_))));
+        assert_eq!(checked_slice(s, 2..9), Err(ParseError::MissingPair));
+    }
+
+    #[test]
+    fn header_string_parse_borrows_from_source() {
+        let cookie_header = "name=value; session=abc;123";
+        let cookies: Vec<_> = Cookie::header_string_parse(cookie_header).filter_map(|parse| parse.ok()).collect();
+
+        assert_eq!(cookies.len(), 2);
+        assert_eq!(cookies[1].value(), "abc;123");
+        // Names and values point into the original string rather than fresh allocations
+        assert_eq!(cookies[0].name().as_ptr(), cookie_header.as_ptr());
+        assert_eq!(cookies[1].value().as_ptr(), cookie_header[20..].as_ptr());
+
+        let owned = String::from(cookie_header);
+        let cookies: Vec<_> = Cookie::header_string_parse(owned).filter_map(|parse| parse.ok()).collect();
+
+        assert_eq!(cookies.len(), 2);
+        assert_eq!(cookies[1].value(), "abc;123");
+    }
+
+    #[test]
+    fn header_string_parse_quoted_values() {
+        let cases = [
+            (r#"name="value with ; semicolon"; other=1"#, vec![("name", "value with ; semicolon"), ("other", "1")]),
+            (r#"a="x; y=z""#, vec![("a", "x; y=z")]),
+            (r#"a=""; b=2"#, vec![("a", ""), ("b", "2")]),
+            (r#"a = "quoted" ;b=2"#, vec![("a", "quoted"), ("b", "2")]),
+            // Escaped quotes don't close the string and are kept verbatim
+            (r#"a="say \"hi;there\""; b=2"#, vec![("a", r#"say \"hi;there\""#), ("b", "2")]),
+            // Quoted regions inside a value are skipped when looking for the real separator
+            (r#"a="x; y=z"; b=1"#, vec![("a", "x; y=z"), ("b", "1")]),
+            (r#"a=x;"q; y=z"; b=1"#, vec![("a", r#"x;"q; y=z""#), ("b", "1")]),
+            // Unterminated quotes fall back to heuristic splitting
+            (r#"a="abc;def; b=2"#, vec![("a", r#""abc;def"#), ("b", "2")]),
+            (r#"a="abc"def; b=2"#, vec![("a", r#""abc"def"#), ("b", "2")]),
+        ];
+
+        for (string, expected) in cases {
+            let cookies: Vec<_> = Cookie::header_string_parse(string).filter_map(|parse| parse.ok()).collect();
+            let actual: Vec<_> = cookies.iter().map(|c| c.name_value()).collect();
+
+            assert_eq!(expected, actual, "{string}");
+        }
+    }
+
+    #[test]
+    fn header_string_parse_size_hint() {
+        let mut cookies = Cookie::header_string_parse("a=1; b=2;; c=3");
+        assert_eq!(cookies.size_hint(), (0, Some(4)));
+
+        cookies.next();
+        assert_eq!(cookies.size_hint(), (0, Some(3)));
+
+        assert_eq!(cookies.by_ref().count(), 2);
+        assert_eq!(cookies.size_hint(), (0, Some(0)));
+        assert_eq!(Cookie::header_string_parse("").size_hint(), (0, Some(0)));
+    }
+
+    #[test]
+    fn header_string_parse_indexed() {
+        let cookie_header = "a=1;  session=abc;123 ; b=2";
+        let indexed: Vec<_> = Cookie::header_string_parse_indexed(cookie_header)
+            .map(|(range, result)| (range, result.unwrap()))
+            .collect();
+
+        assert_eq!(indexed.len(), 3);
+        assert_eq!(indexed[0].0, 0..3);
+        assert_eq!(&cookie_header[indexed[1].0.clone()], "  session=abc;123 ");
+        assert_eq!(indexed[1].1.value(), "abc;123");
+        assert_eq!(&cookie_header[indexed[2].0.clone()], " b=2");
+    }
+
+    #[test]
+    fn header_string_parse_filter() {
+        let cookie_header = "a=1; b=%FF; flag; c=3; b=2";
+        let mut seen = Vec::new();
+        let cookies: Vec<_> = Cookie::header_string_parse_filter(cookie_header, |name| {
+            seen.push(name.to_string());
+            name != "b"
+        })
+        .collect();
+
+        assert_eq!(cookies, [Ok(Cookie::new("a", "1")), Ok(Cookie::new("c", "3"))]);
+        // `flag` is joined into the value of `b`, and the undecodable `b` is never decoded
+        assert_eq!(seen, ["a", "b", "c", "b"]);
+
+        let parser = HeaderStringParser::builder().mode(ParseMode::Strict).max_cookies(1).build();
+        let results: Vec<_> = parser.parse::<Cookie, _>(cookie_header).filter_names(|name| name == "c").collect();
+        assert_eq!(
+            results,
+            [
+                Err(ParseError::MissingPair.into()),
+                Ok(Cookie::new("c", "3")),
+                Err(HeaderParseError::TooManyCookies {
+                    limit: 1
+                })
+            ]
+        );
+    }
+
+    #[test]
+    fn header_string_parse_spans() {
+        let cookie_header = " a = 1 ;session=\"abc;123\"; b=";
+        let spans: Vec<_> = Cookie::header_string_parse_spans(cookie_header).map(Result::unwrap).collect();
+
+        let actual: Vec<_> = spans
+            .iter()
+            .map(|(name, value, _)| (&cookie_header[name.clone()], &cookie_header[value.clone()]))
+            .collect();
+        assert_eq!(actual, [("a", "1"), ("session", "abc;123"), ("b", "")]);
+        assert_eq!((spans[0].0.clone(), spans[0].1.clone()), (1..2, 5..6));
+        assert_eq!(spans[2].1, cookie_header.len()..cookie_header.len());
+        assert_eq!(spans[1].2.value(), "abc;123");
+    }
+
+    #[test]
+    fn header_string_parse_traced() {
+        let cookie_header = "a=1; session=abc;123; b=2";
+        let traced: Vec<_> = Cookie::header_string_parse_traced(cookie_header)
+            .map(|(split, result)| (split, result.unwrap().name().to_string()))
+            .collect();
+
+        assert_eq!(
+            traced,
+            vec![
+                (SplitKind::CleanSeparator, "a".to_string()),
+                (SplitKind::HeuristicJoin, "session".to_string()),
+                (SplitKind::EndOfString, "b".to_string()),
+            ]
+        );
+
+        // Segments buffered by `next_back` keep their own split kind
+        let mut iter = Cookie::header_string_parse(cookie_header);
+        assert_eq!(iter.next_back().unwrap().unwrap().name(), "b");
+        let traced: Vec<_> = iter.traced().map(|(split, result)| (split, result.unwrap().name().to_string())).collect();
+        assert_eq!(
+            traced,
+            vec![(SplitKind::CleanSeparator, "a".to_string()), (SplitKind::HeuristicJoin, "session".to_string())]
+        );
+    }
+
+    #[test]
+    fn header_string_parse_errors() {
+        assert_eq!(Cookie::header_string_parse_errors("a=1; b=2").count(), 0);
+
+        let parser = HeaderStringParser::builder().mode(ParseMode::Strict).max_value_len(3).build();
+        let errors: Vec<_> = parser.parse::<Cookie, _>("a=1; =2; b=1234; c").errors().collect();
+
+        assert_eq!(
+            errors,
+            vec![
+                HeaderParseError::Cookie(ParseError::EmptyName),
+                HeaderParseError::ValueTooLong {
+                    limit: 3
+                },
+                HeaderParseError::Cookie(ParseError::MissingPair),
+            ]
+        );
+    }
+
     #[test]
     #[cfg(feature = "reqwest")]
     fn header_string_parse_reqwest() {
@@ -498,4 +2378,567 @@ mod tests {
         assert_eq!(cookies[0].value(), "abc;123");
         assert_eq!(cookies[1].value(), "value");
     }
+
+    #[test]
+    #[cfg(feature = "reqwest")]
+    fn populate_jar_reqwest() {
+        use crate::reqwest_support::populate_jar;
+        use reqwest::cookie::CookieStore;
+
+        let jar = reqwest::cookie::Jar::default();
+        let url: reqwest::Url = "https://example.com".parse().unwrap();
+
+        let (added, errors) = populate_jar(&jar, &url, "a=1; b=2");
+        assert_eq!(added, 2);
+        assert!(errors.is_empty());
+
+        // The jar doesn't preserve insertion order
+        let header = jar.cookies(&url).unwrap();
+        let mut stored: Vec<_> = header.to_str().unwrap().split("; ").collect();
+        stored.sort();
+        assert_eq!(stored, ["a=1", "b=2"]);
+    }
+
+    #[test]
+    #[cfg(feature = "http")]
+    fn parse_header_value_http() {
+        let value = http::HeaderValue::from_bytes(b"a=caf\xc3\xa9; b=\xff; c=3").unwrap();
+        assert!(value.to_str().is_err());
+
+        let cookies: Vec<_> = http_support::parse_header_value(&value).filter_map(|c| c.ok()).collect();
+        let actual: Vec<_> = cookies.iter().map(|c| c.name_value()).collect();
+
+        assert_eq!(actual, [("a", "café"), ("b", "\u{FFFD}"), ("c", "3")]);
+    }
+
+    #[test]
+    #[cfg(feature = "http")]
+    fn canonical_header_value_http() {
+        let value = http::HeaderValue::from_bytes(b"a=x;y; b=caf\xc3\xa9 \"q\"; c=\xff").unwrap();
+        let canonical = http_support::canonical_header_value(&value);
+
+        assert_eq!(canonical, "a=x%3By; b=caf%C3%A9%20%22q%22; c=%EF%BF%BD");
+        // Splitting at every semicolon, as standard parsers do, finds the same cookies
+        let names: Vec<_> = canonical.to_str().unwrap().split("; ").map(|c| c.split('=').next().unwrap()).collect();
+        assert_eq!(names, ["a", "b", "c"]);
+
+        // Names that aren't tokens are dropped
+        let value = http::HeaderValue::from_bytes("n\u{e9}=1; a=2".as_bytes()).unwrap();
+        assert_eq!(http_support::canonical_header_value(&value), "a=2");
+        assert_eq!(http_support::canonical_header_value(&http::HeaderValue::from_static("")), "");
+    }
+
+    #[test]
+    #[cfg(feature = "headers")]
+    fn typed_cookie_headers() {
+        use headers::HeaderMapExt;
+
+        let cookie = headers_support::parse_typed_cookie("a=x;y; b=caf\u{e9}; c=3");
+        let pairs: Vec<_> = cookie.iter().collect();
+        assert_eq!(pairs, [("a", "x%3By"), ("b", "caf%C3%A9"), ("c", "3")]);
+        assert_eq!(headers_support::parse_typed_cookie("").len(), 0);
+
+        // The standard typed header cuts the value at its semicolon
+        let mut map = http::HeaderMap::new();
+        map.insert(http::header::COOKIE, http::HeaderValue::from_static("a=x;y; c=3"));
+        assert_eq!(map.typed_get::<headers::Cookie>().unwrap().get("a"), Some("x"));
+        assert_eq!(headers_support::typed_cookie(&map).unwrap().get("a"), Some("x%3By"));
+
+        // Values that aren't valid UTF-8 are parsed lossily, and headers are merged in order
+        map.append(http::header::COOKIE, http::HeaderValue::from_bytes(b"d=\xff").unwrap());
+        let cookie = headers_support::typed_cookie(&map).unwrap();
+        let pairs: Vec<_> = cookie.iter().collect();
+        assert_eq!(pairs, [("a", "x%3By"), ("c", "3"), ("d", "%EF%BF%BD")]);
+    }
+
+    #[test]
+    #[cfg(feature = "wasm")]
+    fn parse_to_json_wasm() {
+        let json = wasm_support::parse_to_json("a=\"x;y\"; b=caf%C3%A9; c=%FF");
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value["cookies"], serde_json::json!([["a", "x;y"], ["b", "café"]]));
+        assert_eq!(value["errors"].as_array().unwrap().len(), 1);
+        assert_eq!(wasm_support::parse_to_json(""), r#"{"cookies":[],"errors":[]}"#);
+    }
+
+    #[test]
+    #[cfg(feature = "tracing")]
+    fn tracing_events() {
+        use std::sync::{Arc, Mutex};
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata, Subscriber};
+
+        /// Records the message of every event.
+        struct Messages(Arc<Mutex<Vec<String>>>);
+
+        impl Visit for Messages {
+            fn record_debug(&mut self, field: &Field, value: &dyn core::fmt::Debug) {
+                if field.name() == "message" {
+                    self.0.lock().unwrap().push(format!("{value:?}"));
+                }
+            }
+        }
+
+        impl Subscriber for Messages {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, _: &Attributes<'_>) -> Id {
+                Id::from_u64(1)
+            }
+            fn record(&self, _: &Id, _: &Record<'_>) {}
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+            fn event(&self, event: &Event<'_>) {
+                event.record(&mut Messages(self.0.clone()));
+            }
+            fn enter(&self, _: &Id) {}
+            fn exit(&self, _: &Id) {}
+        }
+
+        let messages = Arc::new(Mutex::new(Vec::new()));
+        tracing::subscriber::with_default(Messages(messages.clone()), || {
+            Cookie::header_string_parse("a=se;cret; ; =x").for_each(drop);
+        });
+
+        let messages = messages.lock().unwrap();
+        assert_eq!(
+            *messages,
+            [
+                "found the real separator",
+                "separator kept in the value by the lookahead",
+                "skipped empty segment",
+                "skipped cookie with an empty name",
+            ]
+        );
+        assert!(!messages.iter().any(|m| m.contains("cret")));
+    }
+
+    #[test]
+    fn header_string_parse_stats() {
+        let (results, stats) = Cookie::header_string_parse_stats("=x;; flag; ;a=1; b=2; ");
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(stats.emitted, 2);
+        assert_eq!(stats.empty_skipped, 3);
+        assert_eq!(stats.missing_equals_skipped, 1);
+        assert_eq!(stats.empty_name_skipped, 1);
+
+        let parser = HeaderStringParser::builder().mode(ParseMode::Strict).build();
+        let mut iter = parser.parse::<Cookie, _>("a=1; flag; =x");
+        assert_eq!(iter.by_ref().filter(|result| result.is_err()).count(), 2);
+        assert_eq!(
+            iter.stats(),
+            ParseStats {
+                emitted: 1,
+                ..ParseStats::default()
+            }
+        );
+    }
+
+    #[test]
+    fn header_string_parse_clone_mid_iteration() {
+        let cookie_header = "a=1; b=2;3; c=4";
+        let mut iter = Cookie::header_string_parse(cookie_header);
+        assert_eq!(iter.next().unwrap().unwrap().name_value(), ("a", "1"));
+
+        let snapshot = iter.clone();
+        let rest: Vec<_> = iter.filter_map(|c| c.ok()).collect();
+        let rest_again: Vec<_> = snapshot.filter_map(|c| c.ok()).collect();
+
+        let names: Vec<_> = rest.iter().map(|c| c.name_value()).collect();
+        assert_eq!(names, [("b", "2;3"), ("c", "4")]);
+        assert_eq!(rest, rest_again);
+        assert!(std::ptr::eq(rest[0].value(), rest_again[0].value()));
+    }
+
+    #[test]
+    fn header_string_parse_with_raw() {
+        let cookie_header = "a=x%20y; b=\"q%3B\"; c=";
+        let pairs: Vec<_> = Cookie::header_string_parse_with_raw(cookie_header)
+            .map(|result| result.map(|(cookie, raw)| (cookie.value().to_string(), raw)).unwrap())
+            .collect();
+
+        let raw: Vec<_> = pairs.iter().map(|(_, raw)| *raw).collect();
+        assert_eq!(raw, ["x%20y", "q%3B", ""]);
+
+        let decoded: Vec<_> = pairs.iter().map(|(value, _)| value.as_str()).collect();
+        #[cfg(feature = "percent-encode")]
+        assert_eq!(decoded, ["x y", "q;", ""]);
+        #[cfg(not(feature = "percent-encode"))]
+        assert_eq!(decoded, raw);
+    }
+
+    #[test]
+    fn header_string_parse_trailing_separators() {
+        let cases = [
+            ("a=1", vec![("a", "1")]),
+            ("a=1;", vec![("a", "1")]),
+            ("a=1; ", vec![("a", "1")]),
+            ("a=1; b=2\r\n", vec![("a", "1"), ("b", "2")]),
+            ("a=1;\r\n", vec![("a", "1")]),
+            ("a=x;y\r\n", vec![("a", "x;y")]),
+            ("a=x;y; \r\n", vec![("a", "x;y")]),
+            ("a=\"q\"\r\n", vec![("a", "q")]),
+            ("a=1\r\n;b=2", vec![("a", "1"), ("b", "2")]),
+        ];
+
+        for (string, expected) in cases {
+            let cookies: Vec<_> = Cookie::header_string_parse(string).filter_map(|parse| parse.ok()).collect();
+
+            let actual: Vec<_> = cookies.iter().map(|c| c.name_value()).collect();
+
+            assert_eq!(expected, actual, "{string:?}");
+        }
+
+        // The line terminator is excluded from the last segment, even when values aren't trimmed
+        let parser = HeaderStringParser::builder().trim_values(false).build();
+        let indexed: Vec<_> = parser.parse::<Cookie, _>("a=1; b=x;y\r\n").indexed().collect();
+        assert_eq!(indexed[1].0, 4..10);
+        assert_eq!(indexed[1].1.as_ref().unwrap().value(), "x;y");
+    }
+
+    #[test]
+    fn reverse_iteration_matches_forward() {
+        let cookie_header = "a=1; b=x;y; c=\"q;r\"; a=2";
+        let forward: Vec<_> = Cookie::header_string_parse(cookie_header).collect();
+        let mut backward: Vec<_> = Cookie::header_string_parse(cookie_header).rev().collect();
+        backward.reverse();
+        assert_eq!(forward, backward);
+
+        // Mixing both ends meets in the middle
+        let mut iter = Cookie::header_string_parse(cookie_header);
+        assert_eq!(iter.next().unwrap().unwrap().name_value(), ("a", "1"));
+        assert_eq!(iter.next_back().unwrap().unwrap().name_value(), ("a", "2"));
+        assert_eq!(iter.size_hint(), (2, Some(2)));
+        assert_eq!(iter.next().unwrap().unwrap().name_value(), ("b", "x;y"));
+        assert_eq!(iter.next_back().unwrap().unwrap().name_value(), ("c", "q;r"));
+        assert!(iter.next().is_none() && iter.next_back().is_none());
+
+        // Last-wins without buffering cookies: keep the first occurrence seen from the back
+        let mut seen = Vec::new();
+        for cookie in Cookie::header_string_parse(cookie_header).rev().flatten() {
+            if !seen.iter().any(|c: &Cookie| c.name() == cookie.name()) {
+                seen.push(cookie);
+            }
+        }
+        let actual: Vec<_> = seen.iter().map(|c| c.name_value()).collect();
+        assert_eq!(actual, [("a", "2"), ("c", "q;r"), ("b", "x;y")]);
+
+        // Limits still apply in forward order
+        let parser = HeaderStringParser::builder().max_cookies(2).build();
+        let results: Vec<Result<Cookie, _>> = parser.parse("a=1; b=2; c=3").rev().collect();
+        let actual: Vec<_> = results.iter().map(|r| r.as_ref().map(|c| c.name())).collect();
+        assert_eq!(
+            actual,
+            [
+                Err(&HeaderParseError::TooManyCookies {
+                    limit: 2
+                }),
+                Ok("b"),
+                Ok("a")
+            ]
+        );
+    }
+
+    #[test]
+    fn long_semicolon_runs() {
+        let cookie_header = format!("a=1{} b=2", "; ".repeat(100_000));
+        let mut iter = Cookie::header_string_parse(cookie_header.as_str());
+
+        let actual: Vec<_> = iter.by_ref().flatten().map(|c| c.name_value().1.to_string()).collect();
+        assert_eq!(actual, ["1", "2"]);
+        assert_eq!(iter.stats().empty_skipped, 99_999);
+
+        // Every `;;` encloses an empty segment, so a bare token is never joined with the separator before it
+        let cookie_header = format!("{}c=3", "b;;".repeat(100_000));
+        let mut iter = Cookie::header_string_parse(cookie_header.as_str());
+
+        let actual: Vec<_> = iter.by_ref().flatten().map(|c| c.name_value().1.to_string()).collect();
+        assert_eq!(actual, ["3"]);
+        assert_eq!((iter.stats().empty_skipped, iter.stats().missing_equals_skipped), (100_000, 100_000));
+    }
+
+    #[test]
+    fn blank_headers_yield_nothing() {
+        let parsers = [
+            HeaderStringParser::new(),
+            HeaderStringParser::builder().mode(ParseMode::Strict).build(),
+            HeaderStringParser::builder().max_cookies(0).max_header_len(8).max_name_len(0).max_value_len(0).build(),
+            HeaderStringParser::builder().error_on_empty_name(true).allow_valueless(true).build(),
+            HeaderStringParser::builder()
+                .whitespace_values(WhitespaceValues::Reject)
+                .validate_cookie_octets(true)
+                .build(),
+            HeaderStringParser::builder().require_space_after_separator(true).strip_header_name(true).build(),
+            HeaderStringParser::builder().mode(ParseMode::Strict).require_space_after_separator(true).build(),
+        ];
+
+        for header in ["", "   ", ";", ";;;", " ; ;\t", "\r\n"] {
+            for parser in &parsers {
+                let iter = parser.parse::<Cookie, _>(header);
+                assert!(iter.is_empty(), "{header:?} with {parser:?}");
+                assert_eq!(iter.collect::<Vec<_>>(), [], "{header:?} with {parser:?}");
+            }
+        }
+
+        let limited = HeaderStringParser::builder().max_header_len(0).build();
+        assert_eq!(limited.parse::<Cookie, _>("").count(), 0);
+
+        let mut iter = Cookie::header_string_parse("a=1; ;");
+        assert!(!iter.is_empty());
+        iter.next();
+        assert!(iter.is_empty());
+    }
+
+    #[test]
+    fn remaining_tracks_consumed_segments() {
+        let mut iter = Cookie::header_string_parse("a=1; b=x;y; c=3");
+        assert_eq!(iter.remaining(), "a=1; b=x;y; c=3");
+
+        assert_eq!(iter.next().unwrap().unwrap().value(), "1");
+        assert_eq!(iter.remaining(), " b=x;y; c=3");
+
+        assert_eq!(iter.next().unwrap().unwrap().value(), "x;y");
+        assert_eq!(iter.remaining(), " c=3");
+
+        assert_eq!(iter.next_back().unwrap().unwrap().value(), "3");
+        assert_eq!(iter.remaining(), "");
+        assert!(iter.next().is_none());
+        assert_eq!(iter.remaining(), "");
+
+        let mut iter = Cookie::header_string_parse("a=1; b=2; c=3");
+        iter.next_back();
+        assert_eq!(iter.remaining(), "a=1; b=2");
+    }
+
+    #[test]
+    fn whitespace_is_consistent() {
+        // Every character `str::trim` removes is treated as whitespace around separators too
+        for ws in [" ", "\t", "\x0b", "\x0c", "\r", "\u{a0}", "\u{2003}"] {
+            let cases = [
+                (format!("a=1;{ws}b=2"), vec![("a", "1"), ("b", "2")]),
+                (format!("a=x;y;{ws}b=2"), vec![("a", "x;y"), ("b", "2")]),
+                (format!("{ws}a=1{ws};{ws}b=2{ws}"), vec![("a", "1"), ("b", "2")]),
+                (format!("a=1;{ws};{ws}b=2"), vec![("a", "1"), ("b", "2")]),
+                (format!("a=1;{ws};{ws}"), vec![("a", "1")]),
+                (format!("a={ws}\"q;r\"{ws}; b=2"), vec![("a", "q;r"), ("b", "2")]),
+                (format!("a=\"q;r\"{ws};b=2"), vec![("a", "q;r"), ("b", "2")]),
+            ];
+
+            for (header, mut expected) in cases {
+                let cookies: Vec<_> = Cookie::header_string_parse(header.as_str()).flatten().collect();
+                let actual: Vec<_> = cookies.iter().map(|c| c.name_value()).collect();
+                assert_eq!(actual, expected, "header: {header:?}");
+
+                // A non-ASCII cookie moves an ASCII header off the byte-only fast path
+                let header = format!("{header}; z=\u{e9}");
+                let cookies: Vec<_> = Cookie::header_string_parse(header.as_str()).flatten().collect();
+                let actual: Vec<_> = cookies.iter().map(|c| c.name_value()).collect();
+                expected.push(("z", "\u{e9}"));
+                assert_eq!(actual, expected, "header: {header:?}");
+            }
+
+            // Whitespace inside a joined value is kept verbatim
+            let header = format!("a=x;{ws}y");
+            let cookie = Cookie::header_string_parse(header.as_str()).next().unwrap().unwrap();
+            assert_eq!(cookie.value(), format!("x;{ws}y"));
+
+            let parser = HeaderStringParser::builder().allow_valueless(true).build();
+            let header = format!("a=1;{ws}flag;{ws}b=2");
+            let cookies: Vec<Cookie> = parser.parse(header.as_str()).flatten().collect();
+            let actual: Vec<_> = cookies.iter().map(|c| c.name_value()).collect();
+            assert_eq!(actual, [("a", "1"), ("flag", ""), ("b", "2")], "header: {header:?}");
+        }
+    }
+
+    #[test]
+    fn base_offset_shifts_ranges() {
+        let buffer = "Host: example.com\r\nCookie: a=1; b=x;y\r\n";
+        let start = buffer.find("a=").unwrap();
+        let end = buffer[start..].find("\r\n").unwrap() + start;
+        let parser = HeaderStringParser::builder().base_offset(start).build();
+
+        let ranges: Vec<_> = parser.parse::<Cookie, _>(&buffer[start..end]).indexed().map(|(r, _)| r).collect();
+        let segments: Vec<_> = ranges.into_iter().map(|r| &buffer[r]).collect();
+        assert_eq!(segments, ["a=1", " b=x;y"]);
+
+        let spans: Vec<_> = parser.parse::<Cookie, _>(&buffer[start..end]).spans().flatten().collect();
+        let pairs: Vec<_> = spans.into_iter().map(|(name, value, _)| (&buffer[name], &buffer[value])).collect();
+        assert_eq!(pairs, [("a", "1"), ("b", "x;y")]);
+    }
+
+    #[test]
+    fn error_on_empty_name() {
+        // The empty segment between `;;` must not swallow the separator into the next name
+        let cookie_header = "=value; name=val;; =other";
+
+        let mut iter = Cookie::header_string_parse(cookie_header);
+        let actual: Vec<_> = iter.by_ref().collect();
+        assert_eq!(actual, [Ok(Cookie::new("name", "val"))]);
+        assert_eq!(iter.stats().empty_name_skipped, 2);
+
+        let parser = HeaderStringParser::builder().error_on_empty_name(true).build();
+        let actual: Vec<_> = parser.parse(cookie_header).collect();
+        assert_eq!(
+            actual,
+            [Err(ParseError::EmptyName.into()), Ok(Cookie::new("name", "val")), Err(ParseError::EmptyName.into())]
+        );
+    }
+
+    #[test]
+    fn lossy_substitutes_placeholders() {
+        let parser = HeaderStringParser::builder().mode(ParseMode::Strict).max_value_len(4).max_cookies(3).build();
+        let cookie_header = "a=1; flag; =x; long=12345; b=2";
+
+        let cookies: Vec<Cookie> = parser.parse(cookie_header).lossy().collect();
+        let actual: Vec<_> = cookies.iter().map(|c| c.name_value()).collect();
+        assert_eq!(
+            actual,
+            [("a", "1"), ("flag", LOSSY_PLACEHOLDER), ("", LOSSY_PLACEHOLDER), ("long", LOSSY_PLACEHOLDER), ("b", "2")]
+        );
+        assert_eq!(cookies.len(), parser.parse::<Cookie, _>(cookie_header).count());
+
+        let cookies: Vec<Cookie> = parser.parse("a=1; b=2; c=3; d=4").lossy().placeholder(String::from("!")).collect();
+        let actual: Vec<_> = cookies.iter().map(|c| c.name_value()).collect();
+        assert_eq!(actual, [("a", "1"), ("b", "2"), ("c", "3"), ("d", "!")]);
+    }
+
+    /// Reviewed contract for the lenient heuristics. Append a case whenever a mis-split is fixed.
+    mod corpus {
+        use super::*;
+
+        type Case = (&'static str, &'static [(&'static str, &'static str)]);
+
+        const QUOTED: &[Case] = &[
+            ("a=\"x;y\"; b=2", &[("a", "x;y"), ("b", "2")]),
+            ("a=\"\"; b=2", &[("a", ""), ("b", "2")]),
+            ("a=\"x\\\"y\"; b=2", &[("a", "x\\\"y"), ("b", "2")]),
+            ("a=\"x;y\" ; b=2", &[("a", "x;y"), ("b", "2")]),
+            ("a=\"unterminated; b=2", &[("a", "\"unterminated"), ("b", "2")]),
+            ("a=x\"y; b=2", &[("a", "x\"y"), ("b", "2")]),
+            ("a=\"x\"y; b=2", &[("a", "\"x\"y"), ("b", "2")]),
+            ("a=\"x;y\"z; b=2", &[("a", "\"x;y\"z"), ("b", "2")]),
+            // The quote closed by `b="` is not followed by a separator, so `b` is not swallowed
+            ("a=\"x;y; b=\"2\"", &[("a", "\"x;y"), ("b", "2")]),
+        ];
+
+        const EMBEDDED_PAIRS: &[Case] = &[
+            ("a=b=c; d=e", &[("a", "b=c"), ("d", "e")]),
+            ("q=x=1&y=2; z=3", &[("q", "x=1&y=2"), ("z", "3")]),
+            ("token=abc;def;ghi; user=me", &[("token", "abc;def;ghi"), ("user", "me")]),
+            // `;name=` without a space looks exactly like a separator
+            ("data=k1=v1;k2=v2; other=3", &[("data", "k1=v1"), ("k2", "v2"), ("other", "3")]),
+            ("a=x;y=; b=2", &[("a", "x"), ("y", ""), ("b", "2")]),
+            ("a=1; b; c=3", &[("a", "1; b"), ("c", "3")]),
+            ("a=x; =y; b=2", &[("a", "x; =y"), ("b", "2")]),
+        ];
+
+        const SEMICOLONS: &[Case] = &[
+            ("a=1;", &[("a", "1")]),
+            ("a=1; ", &[("a", "1")]),
+            ("a=1;;;", &[("a", "1")]),
+            ("a=1; b=2;", &[("a", "1"), ("b", "2")]),
+            (";a=1", &[("a", "1")]),
+            ("a=;b=", &[("a", ""), ("b", "")]),
+            ("a=1; \t; b=2", &[("a", "1"), ("b", "2")]),
+            ("a=x;;y; b=2", &[("a", "x"), ("b", "2")]),
+            ("a=1;; =x; b=2", &[("a", "1"), ("b", "2")]),
+            ("a=1; ; =x", &[("a", "1")]),
+        ];
+
+        const PERCENT: &[Case] = &[
+            ("a=%; b=2", &[("a", "%"), ("b", "2")]),
+            ("a=%zz; b=2", &[("a", "%zz"), ("b", "2")]),
+            ("a=100%; b=2", &[("a", "100%"), ("b", "2")]),
+            ("a=%%3; b=2", &[("a", "%%3"), ("b", "2")]),
+        ];
+
+        #[cfg(feature = "percent-encode")]
+        const PERCENT_DECODED: &[Case] = &[
+            ("a=%3B; b=2", &[("a", ";"), ("b", "2")]),
+            ("a=%3Bb=2; c=3", &[("a", ";b=2"), ("c", "3")]),
+            ("a=%E2%82%AC; b=2", &[("a", "€"), ("b", "2")]),
+            ("a=%20x%20; b=2", &[("a", " x "), ("b", "2")]),
+        ];
+
+        #[cfg(not(feature = "percent-encode"))]
+        const PERCENT_DECODED: &[Case] =
+            &[("a=%3B; b=2", &[("a", "%3B"), ("b", "2")]), ("a=%3Bb=2; c=3", &[("a", "%3Bb=2"), ("c", "3")])];
+
+        const WHITESPACE: &[Case] = &[
+            ("a=1;\tb=2", &[("a", "1"), ("b", "2")]),
+            (" \t a = 1 ;  b=2\t", &[("a", "1"), ("b", "2")]),
+            ("a=1 ;b=2", &[("a", "1"), ("b", "2")]),
+            ("a=x y; b=2", &[("a", "x y"), ("b", "2")]),
+            ("a=1;\r\nb=2", &[("a", "1"), ("b", "2")]),
+            ("a=1;\u{a0}b=2", &[("a", "1"), ("b", "2")]),
+        ];
+
+        #[test]
+        fn adversarial_corpus() {
+            let tables = [QUOTED, EMBEDDED_PAIRS, SEMICOLONS, PERCENT, PERCENT_DECODED, WHITESPACE];
+
+            for (header, expected) in tables.into_iter().flatten() {
+                let cookies: Vec<_> = Cookie::header_string_parse(*header).flatten().collect();
+                let actual: Vec<_> = cookies.iter().map(|c| c.name_value()).collect();
+
+                assert_eq!(actual, *expected, "header: {header:?}");
+            }
+        }
+    }
+
+    mod no_panic {
+        use super::*;
+        use proptest::prelude::*;
+
+        /// Parsers covering the options that change how the header is scanned.
+        fn parsers() -> impl Strategy<Value = HeaderStringParser> {
+            (any::<bool>(), any::<bool>(), any::<bool>(), prop::sample::select(vec![';', ',', '\n'])).prop_map(
+                |(strict, allow_valueless, trim_values, separator)| {
+                    HeaderStringParser::builder()
+                        .mode(if strict {
+                            ParseMode::Strict
+                        } else {
+                            ParseMode::Lenient
+                        })
+                        .allow_valueless(allow_valueless)
+                        .trim_values(trim_values)
+                        .separator(separator)
+                        .max_value_len(16)
+                        .build()
+                },
+            )
+        }
+
+        /// Strings built from the characters the parser treats specially, plus multibyte ones.
+        fn headers() -> impl Strategy<Value = String> {
+            prop_oneof!["[a-z_=;, \t\n\"\\\\%é🍪]{0,48}", any::<String>()]
+        }
+
+        fn check(parser: &HeaderStringParser, header: &str) {
+            for (range, _) in parser.parse::<Cookie, _>(header).indexed() {
+                assert!(range.start <= range.end && range.end <= header.len());
+                assert!(header.is_char_boundary(range.start) && header.is_char_boundary(range.end));
+            }
+            for (name, value) in parser.parse_pairs(header).flatten() {
+                assert!(header.contains(name) && header.contains(value));
+            }
+        }
+
+        proptest! {
+            #[test]
+            fn parse_never_panics(parser in parsers(), header in headers()) {
+                check(&parser, &header);
+            }
+
+            #[test]
+            fn parse_bytes_never_panics(parser in parsers(), bytes in any::<Vec<u8>>()) {
+                parser.parse_bytes::<Cookie>(&bytes).for_each(drop);
+                let parser = HeaderStringParser { utf8_mode: Utf8Mode::Strict, ..parser };
+                parser.parse_bytes::<Cookie>(&bytes).for_each(drop);
+            }
+        }
+    }
 }
@@ -0,0 +1,142 @@
+//! Parsing of cookies spread over multiple header lines.
+
+use crate::{CookieBuilder, HeaderParseError, HeaderStringCookies, HeaderStringParser};
+use alloc::borrow::Cow;
+use cookie::Cookie;
+
+/// Iterator over the cookies in several `Cookie` header lines.
+///
+/// The lines are parsed in order as if they were joined with `; `, except that each line is
+/// scanned separately: the lookahead heuristics never look past the end of a line, so a value
+/// at the end of one line is never merged with the start of the next. Limits on the number of
+/// cookies apply to all lines together, while limits on the header length apply to each line.
+///
+/// Created by [`parse_multi`] or [`HeaderStringParser::parse_multi`].
+pub struct MultiHeaderStringCookies<'c, C: CookieBuilder<'c>, I> {
+    lines: I,
+    parser: HeaderStringParser,
+    current: Option<HeaderStringCookies<'c, C>>,
+    // The number of cookies produced by the lines already finished.
+    emitted: usize,
+    // Set once a terminal error ends the iteration for all lines.
+    done: bool,
+}
+
+impl<'c, C: CookieBuilder<'c>, I> MultiHeaderStringCookies<'c, C, I> {
+    pub(crate) fn new(lines: I, parser: HeaderStringParser) -> Self {
+        MultiHeaderStringCookies {
+            lines,
+            parser,
+            current: None,
+            emitted: 0,
+            done: false,
+        }
+    }
+}
+
+impl<'c, C, I> Iterator for MultiHeaderStringCookies<'c, C, I>
+where
+    C: CookieBuilder<'c>,
+    I: Iterator<Item = &'c str>,
+{
+    type Item = Result<C, HeaderParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            if let Some(current) = &mut self.current {
+                if let Some(result) = current.next() {
+                    self.done = matches!(result, Err(HeaderParseError::TooManyCookies { .. }));
+                    return Some(result);
+                }
+                self.emitted = current.emitted;
+            }
+
+            let line = self.lines.next()?;
+            // Carry the count over so that `max_cookies` applies to all lines together
+            self.current = Some(HeaderStringCookies {
+                emitted: self.emitted,
+                ..HeaderStringCookies::new(Cow::Borrowed(line), self.parser.clone())
+            });
+        }
+
+        None
+    }
+}
+
+impl HeaderStringParser {
+    /// Parse several `Cookie` header lines using this parser's configuration, as if they were
+    /// joined with `; `.
+    ///
+    /// See [`MultiHeaderStringCookies`] for how the lines are combined.
+    pub fn parse_multi<'c, C, I>(&self, lines: I) -> MultiHeaderStringCookies<'c, C, I::IntoIter>
+    where
+        C: CookieBuilder<'c>,
+        I: IntoIterator<Item = &'c str>,
+    {
+        MultiHeaderStringCookies::new(lines.into_iter(), self.clone())
+    }
+}
+
+/// Parse several `Cookie` header lines with the default configuration, as if they were joined
+/// with `; `.
+///
+/// HTTP/2 and HTTP/3 clients may split the cookies of a request over multiple header fields. Each
+/// line is scanned separately, so a semicolon-containing value at the end of one line is never
+/// merged with the next line. See [`MultiHeaderStringCookies`].
+///
+/// # Example
+///
+/// ```
+/// use ri_cookie_header_string::parse_multi;
+///
+/// let raw = "a=x;y\r\nb=1;2";
+/// let cookies: Vec<_> = parse_multi(raw.split("\r\n")).filter_map(|result| result.ok()).collect();
+///
+/// assert_eq!(cookies[0].value(), "x;y");
+/// assert_eq!(cookies[1].value(), "1;2");
+/// ```
+pub fn parse_multi<'c, I>(lines: I) -> MultiHeaderStringCookies<'c, Cookie<'c>, I::IntoIter>
+where
+    I: IntoIterator<Item = &'c str>,
+{
+    HeaderStringParser::new().parse_multi(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_multi_keeps_lines_apart() {
+        let lines = ["session=abc;123; theme=dark;", "data=x;y;z", "", " last=q;r "];
+        let cookies: Vec<_> = parse_multi(lines).filter_map(|result| result.ok()).collect();
+
+        let actual: Vec<_> = cookies.iter().map(|c| c.name_value()).collect();
+        assert_eq!(actual, [("session", "abc;123"), ("theme", "dark"), ("data", "x;y;z"), ("last", "q;r")]);
+
+        // Joining the lines first would merge the start of the second line into `a`'s value
+        let lines = ["a=x", "y; b=2"];
+        let cookies: Vec<_> = parse_multi(lines).filter_map(|result| result.ok()).collect();
+        let actual: Vec<_> = cookies.iter().map(|c| c.name_value()).collect();
+        assert_eq!(actual, [("a", "x"), ("b", "2")]);
+
+        let joined = lines.join("; ");
+        let cookies: Vec<_> = crate::name_value_pairs(&joined).filter_map(|result| result.ok()).collect();
+        assert_eq!(cookies, [("a", "x; y"), ("b", "2")]);
+    }
+
+    #[test]
+    fn parse_multi_limits_apply_across_lines() {
+        let parser = HeaderStringParser::builder().max_cookies(3).build();
+        let results: Vec<Result<Cookie, _>> = parser.parse_multi(["a=1; b=2", "c=3; d=4", "e=5"]).collect();
+
+        assert_eq!(results.len(), 4);
+        assert!(results[..3].iter().all(Result::is_ok));
+        assert_eq!(
+            results[3],
+            Err(HeaderParseError::TooManyCookies {
+                limit: 3
+            })
+        );
+    }
+}
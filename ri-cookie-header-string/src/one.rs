@@ -0,0 +1,167 @@
+//! Parsing of a single cookie, from a header expected to hold exactly one or from a lone
+//! `name=value` token.
+
+use crate::{CookieBuilder, HeaderParseError, HeaderStringCookies, HeaderStringParser, SegmentRead, SkipReason};
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use cookie::{Cookie, ParseError};
+
+impl HeaderStringParser {
+    /// Parse a header string that must contain exactly one cookie, using this parser's
+    /// configuration.
+    ///
+    /// Segments that fail to parse don't count as cookies. If no cookie is found, the first such
+    /// error is returned, or [`HeaderParseError::CookieCount`] if there was none. If more than one
+    /// cookie is found, [`HeaderParseError::CookieCount`] reports how many; parsing continues to
+    /// the end of the header to count them.
+    ///
+    /// With [`collect_all_errors`](crate::HeaderStringParserBuilder::collect_all_errors)
+    /// enabled, finding no cookie returns every error as [`HeaderParseError::Multiple`] instead
+    /// of just the first.
+    pub fn parse_one<'c, C, S>(&self, string: S) -> Result<C, HeaderParseError>
+    where
+        C: CookieBuilder<'c>,
+        S: Into<Cow<'c, str>>,
+    {
+        let mut cookie = None;
+        let mut errors = Vec::new();
+        let mut found = 0;
+
+        for (segment, result) in self.parse::<C, _>(string).indexed() {
+            match result {
+                Ok(parsed) => {
+                    found += 1;
+                    cookie.get_or_insert(parsed);
+                }
+                Err(err) if self.collect_all_errors || errors.is_empty() => errors.push((segment, err)),
+                Err(_) => {}
+            }
+        }
+
+        match (found, cookie, errors.len()) {
+            (1, Some(cookie), _) => Ok(cookie),
+            (0, _, 1..) if self.collect_all_errors => Err(HeaderParseError::Multiple {
+                errors,
+            }),
+            (0, _, 1..) => Err(errors.swap_remove(0).1),
+            (found, _, _) => Err(HeaderParseError::CookieCount {
+                found,
+            }),
+        }
+    }
+
+    /// Parse a single `name=value` token, such as one segment of a header split by other means,
+    /// using this parser's configuration.
+    ///
+    /// The token is never split: a separator in it is part of the value. Otherwise it is read
+    /// exactly like a segment of a header, with the same trimming, quote handling, decoding and
+    /// validation. A token the iterator would skip is an error instead:
+    /// [`ParseError::MissingPair`] if it is blank or has no `=`, and [`ParseError::EmptyName`]
+    /// if its name is empty.
+    pub fn parse_cookie_pair<'c, C, S>(&self, token: S) -> Result<C, HeaderParseError>
+    where
+        C: CookieBuilder<'c>,
+        S: Into<Cow<'c, str>>,
+    {
+        let mut iter = HeaderStringCookies::new(token.into(), self.clone());
+        match iter.read_segment(0..iter.string.len()) {
+            SegmentRead::Cookie(ranges) => iter.build_item(ranges),
+            SegmentRead::Skipped(SkipReason::Empty | SkipReason::MissingEquals) => Err(ParseError::MissingPair.into()),
+            SegmentRead::Skipped(SkipReason::EmptyName) => Err(ParseError::EmptyName.into()),
+        }
+    }
+}
+
+/// Parse a single `name=value` token using the default configuration.
+///
+/// See [`HeaderStringParser::parse_cookie_pair`].
+///
+/// # Example
+///
+/// ```
+/// use cookie::ParseError;
+/// use ri_cookie_header_string::{HeaderParseError, parse_cookie_pair};
+///
+/// let cookie = parse_cookie_pair(" session = \"abc;123\" ").unwrap();
+/// assert_eq!(cookie.name_value(), ("session", "abc;123"));
+///
+/// assert_eq!(parse_cookie_pair("flag"), Err(HeaderParseError::Cookie(ParseError::MissingPair)));
+/// ```
+pub fn parse_cookie_pair(token: &str) -> Result<Cookie<'_>, HeaderParseError> {
+    HeaderStringParser::new().parse_cookie_pair(token)
+}
+
+/// Parse a header string that must contain exactly one cookie, using the default configuration.
+///
+/// See [`HeaderStringParser::parse_one`].
+///
+/// # Example
+///
+/// ```
+/// use ri_cookie_header_string::{HeaderParseError, parse_one};
+///
+/// assert_eq!(parse_one("session=abc;123").unwrap().value(), "abc;123");
+/// assert_eq!(parse_one("a=1; b=2"), Err(HeaderParseError::CookieCount { found: 2 }));
+/// ```
+pub fn parse_one(header: &str) -> Result<Cookie<'_>, HeaderParseError> {
+    HeaderStringParser::new().parse_one(header)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CookieHeaderStringExt, ParseMode};
+
+    #[test]
+    fn parse_one_requires_exactly_one_cookie() {
+        assert_eq!(parse_one(" a=1; ").unwrap().name_value(), ("a", "1"));
+        assert_eq!(
+            parse_one(""),
+            Err(HeaderParseError::CookieCount {
+                found: 0
+            })
+        );
+        assert_eq!(
+            parse_one("a=1; b=2; c=3"),
+            Err(HeaderParseError::CookieCount {
+                found: 3
+            })
+        );
+
+        let strict = HeaderStringParser::builder().mode(ParseMode::Strict).build();
+        assert_eq!(strict.parse_one::<Cookie, _>("flag"), Err(ParseError::MissingPair.into()));
+        assert_eq!(strict.parse_one::<Cookie, _>("flag; a=1").unwrap().name_value(), ("a", "1"));
+
+        let all = HeaderStringParser::builder().mode(ParseMode::Strict).collect_all_errors(true).build();
+        assert_eq!(
+            all.parse_one::<Cookie, _>("flag; =1"),
+            Err(HeaderParseError::Multiple {
+                errors: alloc::vec![(0..4, ParseError::MissingPair.into()), (5..8, ParseError::EmptyName.into())]
+            })
+        );
+        assert_eq!(all.parse_one::<Cookie, _>("flag; a=1").unwrap().name_value(), ("a", "1"));
+    }
+
+    #[test]
+    fn parse_cookie_pair_matches_iterator() {
+        for token in [" a = 1 ", "a=\"x y\"", "a=b=c", "a=", "a=1;2", "a=%20", "a=\u{e9}"] {
+            let expected = Cookie::header_string_parse(token).next().unwrap();
+            assert_eq!(parse_cookie_pair(token), expected, "token: {token:?}");
+        }
+
+        assert_eq!(parse_cookie_pair("a=x; b=y").unwrap().value(), "x; b=y");
+        assert_eq!(parse_cookie_pair(""), Err(ParseError::MissingPair.into()));
+        assert_eq!(parse_cookie_pair(" ; "), Err(ParseError::MissingPair.into()));
+        assert_eq!(parse_cookie_pair("flag"), Err(ParseError::MissingPair.into()));
+        assert_eq!(parse_cookie_pair(" =x"), Err(ParseError::EmptyName.into()));
+
+        let parser = HeaderStringParser::builder().allow_valueless(true).reject_control_chars(true).build();
+        assert_eq!(parser.parse_cookie_pair::<Cookie, _>("flag").unwrap().name_value(), ("flag", ""));
+        assert_eq!(
+            parser.parse_cookie_pair::<Cookie, _>("a=\u{7}"),
+            Err(HeaderParseError::ControlCharacter {
+                byte: 7
+            })
+        );
+    }
+}
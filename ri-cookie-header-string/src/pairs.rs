@@ -0,0 +1,174 @@
+//! Lightweight parsing into name/value pairs without constructing cookies.
+
+use crate::{HeaderParseError, HeaderStringCookies, HeaderStringParser};
+use alloc::borrow::Cow;
+use alloc::string::String;
+use cookie::Cookie;
+
+/// Iterator over borrowed `(name, value)` pairs in a header string.
+///
+/// Splits the header exactly like [`HeaderStringCookies`], but yields slices of the source
+/// instead of constructing cookies, so it never allocates per cookie. Values are raw: they
+/// are not passed through the parser's [`ValueDecoder`](crate::ValueDecoder), though the
+/// quotes around a fully double-quoted value are still removed in lenient mode.
+///
+/// Created by [`name_value_pairs`] or [`HeaderStringParser::parse_pairs`].
+#[derive(Clone)]
+pub struct NameValuePairs<'c> {
+    source: &'c str,
+    // The cookie type is unused; the inner iterator only supplies ranges.
+    inner: HeaderStringCookies<'c, Cookie<'c>>,
+}
+
+impl<'c> NameValuePairs<'c> {
+    pub(crate) fn new(source: &'c str, parser: HeaderStringParser) -> Self {
+        NameValuePairs {
+            source,
+            inner: HeaderStringCookies::new(Cow::Borrowed(source), parser),
+        }
+    }
+}
+
+impl<'c> Iterator for NameValuePairs<'c> {
+    type Item = Result<(&'c str, &'c str), HeaderParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, ranges) = self.inner.next_ranges()?;
+
+        Some(ranges.map(|(name, value)| {
+            self.inner.emitted += 1;
+            (&self.source[name], &self.source[value])
+        }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// Iterator over owned `(name, value)` pairs in a header string.
+///
+/// Splits the header exactly like [`HeaderStringCookies`] and decodes values with the parser's
+/// [`ValueDecoder`](crate::ValueDecoder), but builds the strings directly from the header
+/// instead of going through a [`CookieBuilder`](crate::CookieBuilder), so consumers don't need
+/// the `cookie` crate's types in their signatures.
+///
+/// Created by [`header_string_parse_pairs_owned`] or [`HeaderStringParser::parse_pairs_owned`].
+#[derive(Clone)]
+pub struct OwnedPairs<'c> {
+    // The cookie type is unused; the inner iterator only supplies ranges.
+    inner: HeaderStringCookies<'c, Cookie<'c>>,
+}
+
+impl<'c> OwnedPairs<'c> {
+    pub(crate) fn new(string: Cow<'c, str>, parser: HeaderStringParser) -> Self {
+        OwnedPairs {
+            inner: HeaderStringCookies::new(string, parser),
+        }
+    }
+}
+
+impl Iterator for OwnedPairs<'_> {
+    type Item = Result<(String, String), HeaderParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, ranges) = self.inner.next_ranges()?;
+
+        Some(ranges.and_then(|(name, value)| {
+            let name = &self.inner.string[name];
+            let value = self.inner.parser.decode(name, &self.inner.string[value])?.into_owned();
+            self.inner.emitted += 1;
+            Ok((self.inner.parser.normalize_name(name).into_owned(), value))
+        }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// Parse a cookie header string into owned, decoded `(name, value)` pairs with the default
+/// configuration.
+///
+/// See [`OwnedPairs`].
+///
+/// # Example
+///
+/// ```
+/// use ri_cookie_header_string::header_string_parse_pairs_owned;
+///
+/// let pairs: Vec<(String, String)> =
+///     header_string_parse_pairs_owned(String::from("a=1; b=x;y")).filter_map(|pair| pair.ok()).collect();
+///
+/// assert_eq!(pairs, [("a".to_string(), "1".to_string()), ("b".into(), "x;y".into())]);
+/// ```
+pub fn header_string_parse_pairs_owned<'c, S>(string: S) -> OwnedPairs<'c>
+where
+    S: Into<Cow<'c, str>>,
+{
+    HeaderStringParser::new().parse_pairs_owned(string)
+}
+
+/// Parse a cookie header string into borrowed `(name, value)` pairs with the default
+/// configuration.
+///
+/// This is the fastest way to inspect a header read-only. See [`NameValuePairs`].
+///
+/// # Example
+///
+/// ```
+/// use ri_cookie_header_string::name_value_pairs;
+///
+/// let pairs: Vec<_> = name_value_pairs("session=abc;123; theme=dark").filter_map(|pair| pair.ok()).collect();
+///
+/// assert_eq!(pairs, [("session", "abc;123"), ("theme", "dark")]);
+/// ```
+pub fn name_value_pairs(string: &str) -> NameValuePairs<'_> {
+    HeaderStringParser::new().parse_pairs(string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseMode;
+    use cookie::ParseError;
+
+    #[test]
+    fn name_value_pairs_borrow_raw_values() {
+        let cookie_header = "a=\"quoted\"; b=100%25; c=x;y";
+        let pairs: Vec<_> = name_value_pairs(cookie_header).filter_map(|pair| pair.ok()).collect();
+
+        assert_eq!(pairs, [("a", "quoted"), ("b", "100%25"), ("c", "x;y")]);
+        assert!(cookie_header.as_bytes().as_ptr_range().contains(&pairs[1].1.as_ptr()));
+    }
+
+    #[test]
+    fn owned_pairs_decode_values() {
+        let pairs: Vec<_> = header_string_parse_pairs_owned("a=\"quoted\"; b=100%25; c=x;y").collect();
+        let pairs: Vec<_> = pairs.into_iter().map(Result::unwrap).collect();
+
+        let actual: Vec<_> = pairs.iter().map(|(name, value)| (name.as_str(), value.as_str())).collect();
+        #[cfg(feature = "percent-encode")]
+        assert_eq!(actual, [("a", "quoted"), ("b", "100%"), ("c", "x;y")]);
+        #[cfg(not(feature = "percent-encode"))]
+        assert_eq!(actual, [("a", "quoted"), ("b", "100%25"), ("c", "x;y")]);
+    }
+
+    #[test]
+    fn name_value_pairs_errors_and_limits() {
+        let parser = HeaderStringParser::builder().mode(ParseMode::Strict).max_cookies(2).build();
+        let results: Vec<_> = parser.parse_pairs("a=1; flag; b=2; c=3").collect();
+
+        assert_eq!(
+            results,
+            [
+                Ok(("a", "1")),
+                Err(ParseError::MissingPair.into()),
+                Ok(("b", "2")),
+                Err(HeaderParseError::TooManyCookies {
+                    limit: 2
+                }),
+            ]
+        );
+    }
+}
@@ -0,0 +1,1524 @@
+//! Configurable parser for cookie header strings.
+
+use crate::decode::decode_default;
+use crate::is_token_byte;
+use crate::{
+    BoundaryStrategy, CookieBuilder, DefaultStrategy, HeaderParseError, HeaderStringCookies, NameValuePairs,
+    OwnedPairs, RawValueHeaderStringCookies, ValueDecoder,
+};
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::sync::Arc;
+use core::ops::Range;
+
+/// How the parser decides whether a semicolon separates two cookies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParseMode {
+    /// Use lookahead heuristics so that semicolons inside unquoted values are kept as part
+    /// of the value. This is the default.
+    #[default]
+    Lenient,
+    /// Treat every semicolon as a cookie separator, like the `cookie` crate's `SplitCookies`.
+    ///
+    /// Segments without a `=` or with an empty name are reported as errors rather than skipped.
+    ///
+    /// This is faster and predictable, and is appropriate when the header comes from a
+    /// trusted, spec-compliant source.
+    Strict,
+}
+
+/// How byte input that is not valid UTF-8 is handled.
+///
+/// Only applies to headers parsed from bytes, e.g. with [`HeaderStringParser::parse_bytes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Utf8Mode {
+    /// Replace invalid sequences with `U+FFFD` and keep parsing. This is the default.
+    #[default]
+    Lossy,
+    /// Report each cookie containing an invalid sequence as
+    /// [`HeaderParseError::InvalidUtf8`], while still parsing the remaining cookies.
+    ///
+    /// [`HeaderParseError::InvalidUtf8`]: crate::HeaderParseError::InvalidUtf8
+    Strict,
+}
+
+/// How a value consisting only of whitespace, such as in `name=   `, is handled.
+///
+/// Applies to values that are non-empty but contain nothing except whitespace. A truly empty
+/// value such as in `name=` is always accepted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WhitespaceValues {
+    /// Trim the value to the empty string, making `name=   ` indistinguishable from `name=`.
+    /// This is the default.
+    #[default]
+    Trim,
+    /// Report the cookie as [`HeaderParseError::WhitespaceOnlyValue`] and continue with the
+    /// next cookie.
+    ///
+    /// [`HeaderParseError::WhitespaceOnlyValue`]: crate::HeaderParseError::WhitespaceOnlyValue
+    Reject,
+    /// Keep the whitespace verbatim, for systems that use e.g. a single space as a sentinel.
+    Preserve,
+}
+
+/// How the legacy RFC 2965 attributes `$Version`, `$Path`, `$Domain` and `$Port` are handled.
+///
+/// Old clients mix these into the `Cookie` header as pseudo-cookies, e.g.
+/// `$Version=1; name=val; $Path=/`. Any name starting with `$` is treated as such an attribute
+/// unless they are parsed as cookies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LegacyAttributes {
+    /// Parse attributes as ordinary cookies named e.g. `$Path`. This is the default.
+    #[default]
+    AsCookies,
+    /// Drop attributes from the output.
+    Skip,
+    /// Attach the attributes following a cookie to it with
+    /// [`CookieBuilder::set_legacy_attribute`], which for `cookie::Cookie` sets its path and
+    /// domain. Attributes that don't follow a cookie, such as a leading `$Version`, are dropped.
+    ///
+    /// Only iterators that construct cookies attach attributes; the name/value pair iterators
+    /// drop them.
+    Attach,
+}
+
+/// Which bytes may appear in a cookie name when deciding where a cookie starts.
+///
+/// The lenient parser only splits at a separator that is followed by something that looks like
+/// a cookie name and a `=`, so a name containing other bytes is taken as a continuation of the
+/// previous value. This set is used for every such check: the boundary heuristic, its fast path
+/// and the detection of cookies folded with commas. Names are not otherwise validated.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum NameChars {
+    /// ASCII letters, digits, `_` and `-`. This is the default.
+    #[default]
+    Default,
+    /// The RFC 7230 `token` characters allowed in a cookie name by RFC 6265: ASCII letters and
+    /// digits and ``!#$%&'*+-.^_`|~``.
+    RfcToken,
+    /// Bytes for which the predicate returns `true`, for systems that use e.g. `.` or `:` in
+    /// names.
+    Custom(fn(u8) -> bool),
+}
+
+impl NameChars {
+    /// Return whether `b` may appear in a cookie name.
+    #[inline]
+    pub fn allows(&self, b: u8) -> bool {
+        match self {
+            NameChars::Default => matches!(b, b'0'..=b'9' | b'a'..=b'z' | b'A'..=b'Z' | b'_' | b'-'),
+            NameChars::RfcToken => is_token_byte(b),
+            NameChars::Custom(predicate) => predicate(b),
+        }
+    }
+}
+
+impl PartialEq for NameChars {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (NameChars::Default, NameChars::Default) | (NameChars::RfcToken, NameChars::RfcToken) => true,
+            (NameChars::Custom(a), NameChars::Custom(b)) => core::ptr::fn_addr_eq(*a, *b),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for NameChars {}
+
+/// A configured cookie header string parser.
+///
+/// The parser carries the options used by [`HeaderStringCookies`] while scanning a header.
+/// A default-configured parser behaves exactly like [`CookieHeaderStringExt::header_string_parse`].
+///
+/// # Example
+///
+/// ```
+/// use cookie::Cookie;
+/// use ri_cookie_header_string::HeaderStringParser;
+///
+/// let parser = HeaderStringParser::builder().max_cookies(2).build();
+/// let cookies: Vec<Cookie> = parser.parse("a=1; b=2; c=3").filter_map(|result| result.ok()).collect();
+///
+/// assert_eq!(cookies.len(), 2);
+/// ```
+///
+/// [`CookieHeaderStringExt::header_string_parse`]: crate::CookieHeaderStringExt::header_string_parse
+#[derive(Debug, Clone, Default)]
+pub struct HeaderStringParser {
+    pub(crate) mode: ParseMode,
+    pub(crate) max_cookies: Option<usize>,
+    pub(crate) max_header_len: Option<usize>,
+    pub(crate) max_value_len: Option<usize>,
+    pub(crate) max_name_len: Option<usize>,
+    pub(crate) max_separator_scan: Option<usize>,
+    pub(crate) case_insensitive_names: bool,
+    pub(crate) reject_prefixed_names: bool,
+    pub(crate) lowercase_names: bool,
+    pub(crate) allow_valueless: bool,
+    pub(crate) error_on_empty_name: bool,
+    pub(crate) keep_value_whitespace: bool,
+    pub(crate) whitespace_values: WhitespaceValues,
+    pub(crate) reject_control_chars: bool,
+    pub(crate) validate_cookie_octets: bool,
+    pub(crate) max_value_equals: Option<usize>,
+    pub(crate) separator: Option<u8>,
+    pub(crate) strip_header_name: bool,
+    pub(crate) opaque_names: Option<Arc<[String]>>,
+    pub(crate) opaque_sentinel: Option<Arc<str>>,
+    pub(crate) legacy_attributes: LegacyAttributes,
+    pub(crate) boundary_strategy: Option<Arc<dyn BoundaryStrategy>>,
+    pub(crate) utf8_mode: Utf8Mode,
+    pub(crate) decoder: Option<Arc<dyn ValueDecoder>>,
+    pub(crate) decode_only: Option<Arc<[String]>>,
+    pub(crate) raw_on_decode_error: bool,
+    pub(crate) plus_as_space: bool,
+    pub(crate) require_space_after_separator: bool,
+    pub(crate) comma_as_separator: bool,
+    pub(crate) single_quotes: bool,
+    pub(crate) name_chars: NameChars,
+    pub(crate) collect_all_errors: bool,
+    pub(crate) encode_on_serialize: Option<bool>,
+    pub(crate) base_offset: usize,
+}
+
+impl HeaderStringParser {
+    /// Create a parser with the default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a builder for configuring a parser.
+    pub fn builder() -> HeaderStringParserBuilder {
+        HeaderStringParserBuilder::new()
+    }
+
+    /// Parse a cookie header string using this parser's configuration.
+    pub fn parse<'c, C, S>(&self, string: S) -> HeaderStringCookies<'c, C>
+    where
+        C: CookieBuilder<'c>,
+        S: Into<Cow<'c, str>>,
+    {
+        HeaderStringCookies::new(string.into(), self.clone())
+    }
+
+    /// Parse a cookie header string into borrowed `(name, value)` pairs using this parser's
+    /// configuration, without constructing cookies.
+    ///
+    /// The configured [`ValueDecoder`] is not applied; see [`NameValuePairs`].
+    pub fn parse_pairs<'c>(&self, string: &'c str) -> NameValuePairs<'c> {
+        NameValuePairs::new(string, self.clone())
+    }
+
+    /// Parse a cookie header string into owned, decoded `(name, value)` pairs using this
+    /// parser's configuration, without constructing cookies.
+    ///
+    /// See [`OwnedPairs`].
+    pub fn parse_pairs_owned<'c, S>(&self, string: S) -> OwnedPairs<'c>
+    where
+        S: Into<Cow<'c, str>>,
+    {
+        OwnedPairs::new(string.into(), self.clone())
+    }
+
+    /// Parse a cookie header string using this parser's configuration, yielding each cookie
+    /// together with its raw, undecoded value.
+    ///
+    /// See [`RawValueHeaderStringCookies`].
+    pub fn parse_with_raw<'c, C>(&self, string: &'c str) -> RawValueHeaderStringCookies<'c, C>
+    where
+        C: CookieBuilder<'c>,
+    {
+        RawValueHeaderStringCookies::new(string, self.clone())
+    }
+
+    /// Parse a raw cookie header that may not be valid UTF-8 using this parser's configuration.
+    ///
+    /// See [`Utf8Mode`] for how invalid sequences are handled. Byte ranges reported by the
+    /// iterator refer to the header after invalid sequences have been replaced.
+    pub fn parse_bytes<'c, C>(&self, bytes: &'c [u8]) -> HeaderStringCookies<'c, C>
+    where
+        C: CookieBuilder<'c>,
+    {
+        HeaderStringCookies::from_bytes(bytes, self.clone())
+    }
+
+    /// Return the byte that separates cookies.
+    #[inline]
+    pub(crate) fn separator(&self) -> u8 {
+        self.separator.unwrap_or(b';')
+    }
+
+    /// Return whether commas followed by a cookie separate cookies, in addition to the
+    /// separator.
+    #[inline]
+    pub(crate) fn folds_commas(&self) -> bool {
+        self.comma_as_separator && self.separator() != b','
+    }
+
+    /// Return the index of the quote closing a quoted string starting at `start`, honoring
+    /// single quotes if they are enabled.
+    #[inline]
+    pub(crate) fn find_closing_quote(&self, bytes: &[u8], start: usize) -> Option<usize> {
+        match bytes.get(start) {
+            Some(b'\'') if self.single_quotes => crate::find_closing(bytes, start, b'\''),
+            _ => crate::find_closing_quote(bytes, start),
+        }
+    }
+
+    /// Shift a range of the parsed string by the configured base offset.
+    #[inline]
+    pub(crate) fn offset(&self, range: Range<usize>) -> Range<usize> {
+        range.start + self.base_offset..range.end + self.base_offset
+    }
+
+    /// Return whether the separator at `pos` ends the current cookie, according to the
+    /// configured [`BoundaryStrategy`] or the [`DefaultStrategy`] for this configuration.
+    #[inline]
+    pub(crate) fn is_separator(&self, bytes: &[u8], pos: usize) -> bool {
+        match &self.boundary_strategy {
+            Some(strategy) => strategy.is_separator(bytes, pos),
+            None => DefaultStrategy {
+                separator: self.separator(),
+                allow_valueless: self.allow_valueless,
+                legacy_names: self.legacy_attributes != LegacyAttributes::AsCookies,
+                name_chars: self.name_chars,
+            }
+            .is_separator(bytes, pos),
+        }
+    }
+
+    /// Return the name a cookie is stored under, lowercased if configured.
+    pub(crate) fn normalize_name<'a>(&self, name: &'a str) -> Cow<'a, str> {
+        if self.lowercase_names && name.bytes().any(|b| b.is_ascii_uppercase()) {
+            Cow::Owned(name.to_ascii_lowercase())
+        } else {
+            Cow::Borrowed(name)
+        }
+    }
+
+    /// Decode the raw value of the cookie called `name` with the configured decoder, or the
+    /// default one, unless decoding is restricted to other names.
+    ///
+    /// Failures are reported as [`HeaderParseError::DecodeFailed`] carrying the raw value.
+    #[inline]
+    pub(crate) fn decode<'a>(&self, name: &str, value: &'a str) -> Result<Cow<'a, str>, HeaderParseError> {
+        if let Some(names) = &self.decode_only
+            && !names.iter().any(|decoded| decoded == name)
+        {
+            return Ok(Cow::Borrowed(value));
+        }
+
+        let decoded = if self.plus_as_space && value.contains('+') {
+            let spaced = value.replace('+', " ");
+            self.run_decoder(&spaced).map(|decoded| Cow::Owned(decoded.into_owned()))
+        } else {
+            self.run_decoder(value)
+        };
+        match decoded {
+            Err(_) if self.raw_on_decode_error => Ok(Cow::Borrowed(value)),
+            Err(error) => Err(HeaderParseError::DecodeFailed {
+                raw: value.into(),
+                error,
+            }),
+            Ok(decoded) => Ok(decoded),
+        }
+    }
+
+    /// Decode a value with the configured decoder, or the default one.
+    #[inline]
+    fn run_decoder<'a>(&self, value: &'a str) -> Result<Cow<'a, str>, cookie::ParseError> {
+        match &self.decoder {
+            Some(decoder) => decoder.decode(value),
+            None => decode_default(value),
+        }
+    }
+}
+
+/// Builder for [`HeaderStringParser`].
+#[derive(Debug, Clone, Default)]
+pub struct HeaderStringParserBuilder {
+    parser: HeaderStringParser,
+}
+
+impl HeaderStringParserBuilder {
+    /// Create a builder with the default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the [`ParseMode`] used to split cookies.
+    ///
+    /// Defaults to [`ParseMode::Lenient`].
+    pub fn mode(mut self, mode: ParseMode) -> Self {
+        self.parser.mode = mode;
+        self
+    }
+
+    /// Limit the number of cookies that may be produced successfully.
+    ///
+    /// Once `max` cookies have been produced, the next cookie yields a terminal
+    /// [`HeaderParseError::TooManyCookies`] and the iterator is exhausted afterwards.
+    /// By default the number of cookies is unlimited.
+    ///
+    /// [`HeaderParseError::TooManyCookies`]: crate::HeaderParseError::TooManyCookies
+    pub fn max_cookies(mut self, max: usize) -> Self {
+        self.parser.max_cookies = Some(max);
+        self
+    }
+
+    /// Limit the total length of the header string.
+    ///
+    /// The length is measured in bytes of the input string, not in decoded characters. If the
+    /// header is longer than `max`, the first call to `next()` yields
+    /// [`HeaderParseError::HeaderTooLong`] without scanning the header, and the iterator is
+    /// exhausted afterwards. By default header length is unlimited.
+    ///
+    /// [`HeaderParseError::HeaderTooLong`]: crate::HeaderParseError::HeaderTooLong
+    pub fn max_header_len(mut self, max: usize) -> Self {
+        self.parser.max_header_len = Some(max);
+        self
+    }
+
+    /// Limit the length of each cookie's value, in bytes of the raw (undecoded) value.
+    ///
+    /// Cookies whose value exceeds `max` yield [`HeaderParseError::ValueTooLong`] without the
+    /// value ever being copied; parsing continues with the next cookie. By default value
+    /// length is unlimited.
+    ///
+    /// [`HeaderParseError::ValueTooLong`]: crate::HeaderParseError::ValueTooLong
+    pub fn max_value_len(mut self, max: usize) -> Self {
+        self.parser.max_value_len = Some(max);
+        self
+    }
+
+    /// Limit the length of each cookie's name, in bytes of the trimmed name.
+    ///
+    /// Cookies whose name exceeds `max` yield [`HeaderParseError::NameTooLong`] without the
+    /// name ever being copied; parsing continues with the next cookie. By default name length
+    /// is unlimited.
+    ///
+    /// [`HeaderParseError::NameTooLong`]: crate::HeaderParseError::NameTooLong
+    pub fn max_name_len(mut self, max: usize) -> Self {
+        self.parser.max_name_len = Some(max);
+        self
+    }
+
+    /// Limit how far the lenient parser looks for the real end of a value, in bytes.
+    ///
+    /// When a separator is judged to be part of a value, the parser scans the following
+    /// separators for one that ends the cookie. If none is found within `max` bytes of the
+    /// ambiguous separator, the scan gives up and that first separator is taken as the boundary,
+    /// as in [`ParseMode::Strict`]. This caps the work spent on each ambiguous separator in a
+    /// hostile header, independently of [`max_header_len`](Self::max_header_len). By default
+    /// the scan runs to the end of the header.
+    pub fn max_separator_scan(mut self, max: usize) -> Self {
+        self.parser.max_separator_scan = Some(max);
+        self
+    }
+
+    /// Compare cookie names case-insensitively when deduplicating.
+    ///
+    /// This only affects collectors that resolve duplicate names, such as
+    /// [`HeaderStringCookies::collect_map`]; the plain iterator yields every cookie unchanged.
+    /// Comparison uses ASCII case folding only, which covers valid cookie names; non-ASCII
+    /// characters must match exactly. Defaults to `false`.
+    pub fn case_insensitive_names(mut self, enabled: bool) -> Self {
+        self.parser.case_insensitive_names = enabled;
+        self
+    }
+
+    /// ASCII-lowercase each cookie name before the cookie is constructed.
+    ///
+    /// Unlike [`case_insensitive_names`](Self::case_insensitive_names), this changes the stored
+    /// name, so names that differ only in case collapse into duplicates that collectors such as
+    /// [`HeaderStringCookies::collect_map`] resolve as usual, and [`find_cookie`] must be given
+    /// the lowercase name. Non-ASCII characters are left as is. Options that match names, such
+    /// as [`decode_only`](Self::decode_only), still see the name as it appears in the header.
+    /// The borrowed [`NameValuePairs`] iterator yields names unchanged. Defaults to `false`.
+    ///
+    /// [`HeaderStringCookies::collect_map`]: crate::HeaderStringCookies::collect_map
+    /// [`find_cookie`]: crate::HeaderStringCookies::find_cookie
+    pub fn lowercase_names(mut self, enabled: bool) -> Self {
+        self.parser.lowercase_names = enabled;
+        self
+    }
+
+    /// Report segments with an empty name, such as the leading `=value` of `=value; name=val`,
+    /// instead of skipping them.
+    ///
+    /// Such segments yield a [`ParseError::EmptyName`] wrapped in a `HeaderParseError`, like in
+    /// [`ParseMode::Strict`], and parsing continues with the next cookie. Note that an `=value`
+    /// segment following a cookie is usually joined into that cookie's value by the lenient
+    /// heuristics. Defaults to `false`, which skips them silently.
+    pub fn error_on_empty_name(mut self, enabled: bool) -> Self {
+        self.parser.error_on_empty_name = enabled;
+        self
+    }
+
+    /// Reject cookies whose names carry a `__Secure-` or `__Host-` prefix.
+    ///
+    /// Such cookies yield [`HeaderParseError::PrefixedName`] and parsing continues with the next
+    /// cookie. The attributes that give these prefixes their guarantees are not sent in a
+    /// `Cookie` request header, so this is a defense-in-depth option for servers that never
+    /// issue prefixed cookies. See [`CookiePrefix`]. Defaults to `false`.
+    ///
+    /// [`HeaderParseError::PrefixedName`]: crate::HeaderParseError::PrefixedName
+    /// [`CookiePrefix`]: crate::CookiePrefix
+    pub fn reject_prefixed_names(mut self, enabled: bool) -> Self {
+        self.parser.reject_prefixed_names = enabled;
+        self
+    }
+
+    /// Accept bare tokens without a `=` as cookies with an empty value.
+    ///
+    /// Some non-standard headers send flags like `secure` as bare tokens. When enabled, such a
+    /// segment is emitted as a cookie named after the token, provided the token is a valid
+    /// cookie name; other segments without a `=` are handled as usual. Defaults to `false`,
+    /// which skips them (or reports them in [`ParseMode::Strict`]).
+    pub fn allow_valueless(mut self, enabled: bool) -> Self {
+        self.parser.allow_valueless = enabled;
+        self
+    }
+
+    /// Set the character that separates cookies.
+    ///
+    /// The separator replaces `;` everywhere in the scanning logic, including the lenient
+    /// lookahead heuristics, which lets the parser handle similar delimited key-value formats
+    /// that use e.g. a comma or a newline. Defaults to `;`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `separator` is not an ASCII character other than `=` and `"`.
+    pub fn separator(mut self, separator: char) -> Self {
+        assert!(
+            separator.is_ascii() && !matches!(separator, '=' | '"'),
+            "cookie separator must be an ASCII character other than `=` and `\"`"
+        );
+        self.parser.separator = Some(separator as u8);
+        self
+    }
+
+    /// Treat the values of cookies with the given names as opaque.
+    ///
+    /// An opaque value extends from the `=` after the name up to the sentinel set with
+    /// [`opaque_sentinel`](Self::opaque_sentinel), or to the end of the header if there is none,
+    /// without any separator heuristics. This keeps embedded payloads such as serialized query
+    /// strings (`data=a=1; b=2`) intact, which the heuristics would otherwise split into several
+    /// cookies. Names are matched exactly. By default no name is opaque.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    /// use ri_cookie_header_string::HeaderStringParser;
+    ///
+    /// let parser = HeaderStringParser::builder().opaque_names(["data"]).opaque_sentinel(";;").build();
+    /// let cookies: Vec<Cookie> = parser.parse("data=a=1; b=2;; theme=dark").filter_map(|result| result.ok()).collect();
+    ///
+    /// assert_eq!(cookies[0].value(), "a=1; b=2");
+    /// assert_eq!(cookies[1].value(), "dark");
+    /// ```
+    pub fn opaque_names<I>(mut self, names: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.parser.opaque_names = Some(names.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Set the sentinel that ends the value of a cookie with an opaque name.
+    ///
+    /// The sentinel is consumed like a separator and parsing continues after it. If it doesn't
+    /// occur, the opaque value extends to the end of the header. By default there is no
+    /// sentinel. See [`opaque_names`](Self::opaque_names).
+    pub fn opaque_sentinel(mut self, sentinel: &str) -> Self {
+        self.parser.opaque_sentinel = Some(sentinel.into());
+        self
+    }
+
+    /// Skip a leading `Cookie:` or `Set-Cookie:` header name before parsing.
+    ///
+    /// Convenient for whole header lines copied from logs or browser developer tools. The
+    /// header name is matched ASCII case-insensitively and only when followed by a colon, so a
+    /// cookie named `Cookie` is left alone. Defaults to `false`.
+    pub fn strip_header_name(mut self, enabled: bool) -> Self {
+        self.parser.strip_header_name = enabled;
+        self
+    }
+
+    /// Reject cookies whose raw value contains a control character.
+    ///
+    /// Control characters are the bytes `0x00`–`0x1F` except tab, and `0x7F`. They are never
+    /// valid in a cookie value and could enable header injection if the value is later written
+    /// into another header. The check runs on the raw value before decoding, so percent-encoded
+    /// control characters are not detected. Such cookies yield
+    /// [`HeaderParseError::ControlCharacter`] and parsing continues with the next cookie.
+    /// Defaults to `false`.
+    ///
+    /// [`HeaderParseError::ControlCharacter`]: crate::HeaderParseError::ControlCharacter
+    pub fn reject_control_chars(mut self, enabled: bool) -> Self {
+        self.parser.reject_control_chars = enabled;
+        self
+    }
+
+    /// Restrict values to the RFC 6265 `cookie-octet` set.
+    ///
+    /// Values may then only contain US-ASCII characters other than controls, whitespace, `"`,
+    /// `,`, `;` and `\`, optionally wrapped in a pair of double quotes. Cookies with any other
+    /// byte in their raw value yield [`HeaderParseError::InvalidCookieOctet`] and parsing
+    /// continues with the next cookie. Without this option any value up to the separator is
+    /// accepted. This is typically combined with [`ParseMode::Strict`]. Defaults to `false`.
+    ///
+    /// [`HeaderParseError::InvalidCookieOctet`]: crate::HeaderParseError::InvalidCookieOctet
+    pub fn validate_cookie_octets(mut self, enabled: bool) -> Self {
+        self.parser.validate_cookie_octets = enabled;
+        self
+    }
+
+    /// Limit the number of `=` signs in each cookie's raw value.
+    ///
+    /// A segment is split into name and value at its first `=`, and everything after it is the
+    /// value, so `a=b=c` is the cookie `a` with the value `b=c`. That is also how values joined
+    /// by the lenient lookahead keep any `=` they contain. RFC 6265 allows `=` in values, but a
+    /// serialized value with several of them is often a sign of unencoded `key=value` data or of
+    /// a mis-split header. Cookies whose value has more than `max` `=` signs yield
+    /// [`HeaderParseError::TooManyEquals`] and parsing continues with the next cookie; `0`
+    /// rejects any `=` in a value. This is typically combined with [`ParseMode::Strict`] for
+    /// validation. By default the number is unlimited.
+    ///
+    /// [`HeaderParseError::TooManyEquals`]: crate::HeaderParseError::TooManyEquals
+    pub fn max_value_equals(mut self, max: usize) -> Self {
+        self.parser.max_value_equals = Some(max);
+        self
+    }
+
+    /// Only split on a separator that is followed by whitespace or ends the header.
+    ///
+    /// Well-behaved clients always separate cookies with `; `, so for a client population known
+    /// to do so, a `;` directly followed by anything else can only be part of a value. Every
+    /// `; ` is then a separator and the lookahead heuristics are skipped, in both parse modes.
+    /// Double-quoted values are still taken literally in [`ParseMode::Lenient`]. Defaults to
+    /// `false`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    /// use ri_cookie_header_string::HeaderStringParser;
+    ///
+    /// let parser = HeaderStringParser::builder().require_space_after_separator(true).build();
+    /// let cookies: Vec<Cookie> = parser.parse("data=a;b=c; theme=dark").filter_map(|result| result.ok()).collect();
+    ///
+    /// assert_eq!(cookies[0].value(), "a;b=c");
+    /// assert_eq!(cookies[1].value(), "dark");
+    /// ```
+    pub fn require_space_after_separator(mut self, enabled: bool) -> Self {
+        self.parser.require_space_after_separator = enabled;
+        self
+    }
+
+    /// Also treat values wrapped in single quotes, like `name='val;ue'`, as quoted.
+    ///
+    /// Some broken clients quote values with `'` instead of `"`. With this option such values
+    /// are handled exactly like double-quoted ones in [`ParseMode::Lenient`]: the interior is
+    /// taken literally, separators included, and the quotes are stripped. A quote of the other
+    /// type inside the value doesn't close it, and a backslash escapes the following byte. This
+    /// is opt-in because `'` is otherwise an ordinary value character, so a value that merely
+    /// starts and ends with one, such as `'a'`, loses its quotes. Defaults to `false`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    /// use ri_cookie_header_string::HeaderStringParser;
+    ///
+    /// let parser = HeaderStringParser::builder().single_quotes(true).build();
+    /// let cookies: Vec<Cookie> = parser.parse("a='x; b=1'; c=2").filter_map(|result| result.ok()).collect();
+    ///
+    /// assert_eq!(cookies[0].value(), "x; b=1");
+    /// assert_eq!(cookies[1].value(), "2");
+    /// ```
+    pub fn single_quotes(mut self, enabled: bool) -> Self {
+        self.parser.single_quotes = enabled;
+        self
+    }
+
+    /// Set which bytes may appear in a cookie name when looking for the start of the next cookie
+    /// in [`ParseMode::Lenient`].
+    ///
+    /// A separator followed by a name with a byte outside the set is kept in the previous value,
+    /// so with the default set, ASCII letters, digits, `_` and `-`, a name like `app.id` or
+    /// `ns:key` is joined into the cookie before it. [`NameChars::RfcToken`] accepts every
+    /// character RFC 6265 allows in a name, and [`NameChars::Custom`] takes any predicate. The
+    /// same set is used for every boundary check, including comma folding with
+    /// [`comma_as_separator`](Self::comma_as_separator); it has no effect with a custom
+    /// [`boundary_strategy`](Self::boundary_strategy) or in [`ParseMode::Strict`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    /// use ri_cookie_header_string::{HeaderStringParser, NameChars};
+    ///
+    /// let header = "a=1; app.id=2; ns:key=3";
+    /// let parse = |parser: HeaderStringParser| -> Vec<Cookie> { parser.parse(header).flatten().collect() };
+    ///
+    /// assert_eq!(parse(HeaderStringParser::new())[0].value(), "1; app.id=2; ns:key=3");
+    /// assert_eq!(parse(HeaderStringParser::builder().name_chars(NameChars::RfcToken).build()).len(), 2);
+    ///
+    /// let parser = HeaderStringParser::builder()
+    ///     .name_chars(NameChars::Custom(|b| b.is_ascii_alphanumeric() || matches!(b, b'_' | b'-' | b'.' | b':')))
+    ///     .build();
+    /// assert_eq!(parse(parser).len(), 3);
+    /// ```
+    pub fn name_chars(mut self, chars: NameChars) -> Self {
+        self.parser.name_chars = chars;
+        self
+    }
+
+    /// Also split cookies at commas, for headers that an intermediary folded with `,`.
+    ///
+    /// A comma only separates cookies when it is followed, after optional whitespace, by a name
+    /// made of the [`name_chars`](Self::name_chars) and then a `=`, in both parse modes. Other
+    /// commas stay in the value, so `a=x,y` keeps its value and the comma in a date such as
+    /// `Wed, 21 Oct 2015 07:28:00 GMT` is not mistaken for a separator. A double-quoted value
+    /// is taken literally in [`ParseMode::Lenient`].
+    ///
+    /// The limitation is that a value containing `,name=` is split there: `a=k1=v1,k2=v2`
+    /// becomes the cookies `a` and `k2`. Only enable this for headers known to be folded.
+    /// Has no effect if the [`separator`](Self::separator) is already a comma. Defaults to
+    /// `false`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    /// use ri_cookie_header_string::HeaderStringParser;
+    ///
+    /// let parser = HeaderStringParser::builder().comma_as_separator(true).build();
+    /// let cookies: Vec<Cookie> = parser.parse("a=1, b=x,y; c=3").filter_map(|result| result.ok()).collect();
+    /// let pairs: Vec<_> = cookies.iter().map(|c| c.name_value()).collect();
+    ///
+    /// assert_eq!(pairs, [("a", "1"), ("b", "x,y"), ("c", "3")]);
+    /// ```
+    pub fn comma_as_separator(mut self, enabled: bool) -> Self {
+        self.parser.comma_as_separator = enabled;
+        self
+    }
+
+    /// Trim leading and trailing whitespace from values.
+    ///
+    /// When disabled, the value is taken verbatim from just after the `=` up to the separator,
+    /// which preserves significant whitespace in e.g. signed values; names are always trimmed.
+    /// Trimming happens before decoding, so a percent-encoded space such as `%20` is kept either
+    /// way, while a literal leading or trailing space is only kept when trimming is disabled.
+    /// A quoted value surrounded by whitespace keeps its quotes when trimming is disabled.
+    /// Defaults to `true`.
+    pub fn trim_values(mut self, enabled: bool) -> Self {
+        self.parser.keep_value_whitespace = !enabled;
+        self
+    }
+
+    /// Set how values consisting only of whitespace are handled.
+    ///
+    /// This takes precedence over [`trim_values`](Self::trim_values) for such values, so they
+    /// can be preserved or rejected while other values are still trimmed.
+    /// Defaults to [`WhitespaceValues::Trim`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    /// use ri_cookie_header_string::{HeaderStringParser, WhitespaceValues};
+    ///
+    /// let parser = HeaderStringParser::builder().whitespace_values(WhitespaceValues::Preserve).build();
+    /// let cookies: Vec<Cookie> = parser.parse("sentinel= ; empty=").filter_map(|result| result.ok()).collect();
+    ///
+    /// assert_eq!(cookies[0].value(), " ");
+    /// assert_eq!(cookies[1].value(), "");
+    /// ```
+    pub fn whitespace_values(mut self, mode: WhitespaceValues) -> Self {
+        self.parser.whitespace_values = mode;
+        self
+    }
+
+    /// Only decode the values of cookies with the given names.
+    ///
+    /// Values of all other cookies are taken verbatim, so a literal `%` in a plaintext cookie
+    /// is preserved even with the `percent-encode` feature enabled. Names are matched exactly.
+    /// By default every value is decoded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    /// use ri_cookie_header_string::HeaderStringParser;
+    ///
+    /// let parser = HeaderStringParser::builder().decode_only(["encoded"]).decoder(|value: &str| Ok(value.to_uppercase())).build();
+    /// let cookies: Vec<Cookie> = parser.parse("encoded=abc; plain=100%").filter_map(|result| result.ok()).collect();
+    ///
+    /// assert_eq!(cookies[0].value(), "ABC");
+    /// assert_eq!(cookies[1].value(), "100%");
+    /// ```
+    pub fn decode_only<I>(mut self, names: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.parser.decode_only = Some(names.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Set how legacy RFC 2965 `$` attributes such as `$Path` are handled.
+    ///
+    /// Unless they are parsed as cookies, `; $Name=` is also recognized as a separator by the
+    /// lenient heuristics. Defaults to [`LegacyAttributes::AsCookies`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    /// use ri_cookie_header_string::{HeaderStringParser, LegacyAttributes};
+    ///
+    /// let parser = HeaderStringParser::builder().legacy_attributes(LegacyAttributes::Attach).build();
+    /// let cookies: Vec<Cookie> = parser.parse("$Version=1; name=val; $Path=/").filter_map(|result| result.ok()).collect();
+    ///
+    /// assert_eq!(cookies.len(), 1);
+    /// assert_eq!(cookies[0].path(), Some("/"));
+    /// ```
+    pub fn legacy_attributes(mut self, mode: LegacyAttributes) -> Self {
+        self.parser.legacy_attributes = mode;
+        self
+    }
+
+    /// Set the [`BoundaryStrategy`] that decides whether a semicolon inside a value separates
+    /// cookies in [`ParseMode::Lenient`].
+    ///
+    /// Defaults to the [`DefaultStrategy`] heuristic for this configuration. Strict mode, opaque
+    /// cookies and [`require_space_after_separator`](Self::require_space_after_separator) don't
+    /// consult the strategy.
+    pub fn boundary_strategy<S: BoundaryStrategy + 'static>(mut self, strategy: S) -> Self {
+        self.parser.boundary_strategy = Some(Arc::new(strategy));
+        self
+    }
+
+    /// Keep the raw value of a cookie whose value fails to decode instead of reporting an error.
+    ///
+    /// A stray `%` that doesn't start an escape never fails, so `name=100%done` is always kept
+    /// as is. But an escape such as `%FF` that decodes to invalid UTF-8 makes the
+    /// `PercentDecoder` fail with [`HeaderParseError::DecodeFailed`], which drops an otherwise
+    /// valid cookie. With this option the value is taken verbatim instead. Applies to any
+    /// configured [`ValueDecoder`]. Defaults to `false`.
+    pub fn raw_on_decode_error(mut self, enabled: bool) -> Self {
+        self.parser.raw_on_decode_error = enabled;
+        self
+    }
+
+    /// Also decode `+` as a space, as in `application/x-www-form-urlencoded` data.
+    ///
+    /// Some applications wrongly form-encode cookie values, writing spaces as `+`. With this
+    /// option every `+` in a value that is decoded becomes a space before the
+    /// [`ValueDecoder`] runs, so `a+b` reads as `a b` while `%2B` still decodes to `+`. Values
+    /// excluded by [`decode_only`](Self::decode_only) are left alone. Defaults to `false`, since
+    /// `+` is a legitimate value character, e.g. in base64.
+    #[cfg(feature = "percent-encode")]
+    pub fn plus_as_space(mut self, enabled: bool) -> Self {
+        self.parser.plus_as_space = enabled;
+        self
+    }
+
+    /// Set how byte input that is not valid UTF-8 is handled.
+    ///
+    /// Defaults to [`Utf8Mode::Lossy`].
+    pub fn utf8_mode(mut self, mode: Utf8Mode) -> Self {
+        self.parser.utf8_mode = mode;
+        self
+    }
+
+    /// Set the [`ValueDecoder`] applied to every cookie value.
+    ///
+    /// Defaults to `PercentDecoder` when the `percent-encode` feature is enabled, and to
+    /// [`IdentityDecoder`] otherwise. Cookie names are never decoded.
+    ///
+    /// [`IdentityDecoder`]: crate::IdentityDecoder
+    pub fn decoder<D: ValueDecoder + 'static>(mut self, decoder: D) -> Self {
+        self.parser.decoder = Some(Arc::new(decoder));
+        self
+    }
+
+    /// Percent-encode values that would break re-parsing when serializing with
+    /// [`HeaderStringParser::to_header_string`].
+    ///
+    /// Semicolons, commas, whitespace, quotes, backslashes, control characters, non-ASCII
+    /// characters and `%` are encoded, so a value with an embedded `;` is written as `%3B`. The
+    /// output re-parses to the original values when they are percent-decoded, as the default
+    /// decoder does with the `percent-encode` feature. Defaults to `true` when the
+    /// `percent-encode` feature is enabled and `false` otherwise, like
+    /// [`to_header_string`](crate::to_header_string). Semicolons, commas and control characters
+    /// are escaped even when disabled, and cookies whose name is not a token are always skipped.
+    pub fn encode_on_serialize(mut self, enabled: bool) -> Self {
+        self.parser.encode_on_serialize = Some(enabled);
+        self
+    }
+
+    /// Make the eager helpers report every error in the header instead of stopping at the first.
+    ///
+    /// [`validate`](HeaderStringParser::validate) then scans the whole header, and
+    /// [`parse_one`](HeaderStringParser::parse_one) reports every error when it finds no cookie.
+    /// Errors are returned as [`HeaderParseError::Multiple`] with the byte range of each
+    /// segment, even if there is only one, so a malformed header can be diagnosed in a single
+    /// pass. Terminal errors such as [`HeaderParseError::TooManyCookies`] still end the scan.
+    /// The iterators are unaffected, since they already yield every error. Defaults to `false`.
+    ///
+    /// [`HeaderParseError::Multiple`]: crate::HeaderParseError::Multiple
+    /// [`HeaderParseError::TooManyCookies`]: crate::HeaderParseError::TooManyCookies
+    pub fn collect_all_errors(mut self, enabled: bool) -> Self {
+        self.parser.collect_all_errors = enabled;
+        self
+    }
+
+    /// Set the position of the parsed string within a larger buffer.
+    ///
+    /// The offset is added to every range reported by
+    /// [`indexed`](crate::HeaderStringCookies::indexed) and
+    /// [`spans`](crate::HeaderStringCookies::spans), so that when a header is parsed from a slice
+    /// of e.g. a whole request head, the ranges index the buffer rather than the slice. Parsing
+    /// itself is unaffected. Defaults to `0`.
+    pub fn base_offset(mut self, offset: usize) -> Self {
+        self.parser.base_offset = offset;
+        self
+    }
+
+    /// Finish building the parser.
+    pub fn build(self) -> HeaderStringParser {
+        self.parser
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HeaderParseError;
+    use cookie::{Cookie, ParseError};
+
+    /// Assert that `header` parses to cookies with the `expected` names and values, in order.
+    #[track_caller]
+    fn assert_name_values(parser: &HeaderStringParser, header: &str, expected: &[(&str, &str)]) {
+        let cookies: Vec<Cookie> = parser.parse(header).flatten().collect();
+        let actual: Vec<_> = cookies.iter().map(Cookie::name_value).collect();
+        assert_eq!(actual, expected, "header: {header:?}");
+    }
+
+    #[test]
+    fn default_parser_matches_extension_method() {
+        use crate::CookieHeaderStringExt;
+
+        let cookie_header = "session=abc;123; other=value";
+        let parsed: Vec<Cookie> = HeaderStringParser::new().parse(cookie_header).filter_map(|c| c.ok()).collect();
+        let expected: Vec<_> = Cookie::header_string_parse(cookie_header).filter_map(|c| c.ok()).collect();
+
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn strict_mode_splits_on_every_semicolon() {
+        let cookie_header = "session=abc;123";
+
+        let lenient = HeaderStringParser::builder().mode(ParseMode::Lenient).build();
+        let cookies: Vec<Cookie> = lenient.parse(cookie_header).filter_map(|c| c.ok()).collect();
+        assert_eq!(cookies.len(), 1);
+        assert_eq!(cookies[0].value(), "abc;123");
+
+        // Like `SplitCookies`, the trailing `123` segment is reported as a missing pair.
+        let strict = HeaderStringParser::builder().mode(ParseMode::Strict).build();
+        let results: Vec<_> = strict.parse::<Cookie, _>(cookie_header).collect();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].as_ref().unwrap().value(), "abc");
+        assert_eq!(results[1], Err(HeaderParseError::Cookie(cookie::ParseError::MissingPair)));
+    }
+
+    #[test]
+    fn max_cookies_stops_iteration() {
+        let parser = HeaderStringParser::builder().max_cookies(1).build();
+        let cookies: Vec<Cookie> = parser.parse("a=1; b=2").filter_map(|c| c.ok()).collect();
+
+        assert_eq!(cookies.len(), 1);
+        assert_eq!(cookies[0].name_value(), ("a", "1"));
+    }
+
+    #[test]
+    fn max_cookies_yields_terminal_error() {
+        let parser = HeaderStringParser::builder().max_cookies(2).build();
+        let results: Vec<_> = parser.parse::<Cookie, _>("a=1; b=2; c=3; d=4").collect();
+
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_ok() && results[1].is_ok());
+        assert_eq!(
+            results[2],
+            Err(HeaderParseError::TooManyCookies {
+                limit: 2
+            })
+        );
+
+        // Reaching the limit exactly is not an error
+        let results: Vec<_> = parser.parse::<Cookie, _>("a=1; b=2;").collect();
+        assert!(results.iter().all(|r| r.is_ok()));
+    }
+
+    #[test]
+    fn max_separator_scan_falls_back_to_first_separator() {
+        let header = "a=1;2;3;4; b=2";
+        let pairs = |parser: HeaderStringParser| -> Vec<(String, String)> {
+            parser.parse::<Cookie, _>(header).flatten().map(|c| (c.name().into(), c.value().into())).collect()
+        };
+
+        let unbounded = pairs(HeaderStringParser::new());
+        assert_eq!(unbounded, [("a".into(), "1;2;3;4".into()), ("b".into(), "2".into())]);
+        assert_eq!(pairs(HeaderStringParser::builder().max_separator_scan(6).build()), unbounded);
+
+        // The scan gives up before reaching `; b=`, so `a` ends at its first separator
+        let bounded = pairs(HeaderStringParser::builder().max_separator_scan(5).build());
+        assert_eq!(bounded, [("a".into(), "1".into()), ("b".into(), "2".into())]);
+    }
+
+    #[test]
+    fn max_value_len_rejects_long_values() {
+        let parser = HeaderStringParser::builder().max_value_len(4).build();
+        let results: Vec<_> = parser.parse::<Cookie, _>("a=1234; b=12345; c=x").collect();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap().value(), "1234");
+        assert_eq!(
+            results[1],
+            Err(HeaderParseError::ValueTooLong {
+                limit: 4
+            })
+        );
+        assert_eq!(results[2].as_ref().unwrap().value(), "x");
+    }
+
+    #[test]
+    fn max_name_len_rejects_long_names() {
+        let parser = HeaderStringParser::builder().max_name_len(4).build();
+        let results: Vec<_> = parser.parse::<Cookie, _>(" abcd =1; abcde=2; c=x").collect();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap().name(), "abcd");
+        assert_eq!(
+            results[1],
+            Err(HeaderParseError::NameTooLong {
+                limit: 4
+            })
+        );
+        assert_eq!(results[2].as_ref().unwrap().name(), "c");
+    }
+
+    #[test]
+    fn max_header_len_rejects_before_parsing() {
+        let parser = HeaderStringParser::builder().max_header_len(8).build();
+
+        let results: Vec<_> = parser.parse::<Cookie, _>("a=1; b=2; c=3").collect();
+        assert_eq!(
+            results,
+            vec![Err(HeaderParseError::HeaderTooLong {
+                limit: 8
+            })]
+        );
+
+        let results: Vec<_> = parser.parse::<Cookie, _>("a=1; b=2").collect();
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r.is_ok()));
+    }
+
+    #[test]
+    fn reject_prefixed_names() {
+        let cookie_header = "__Secure-id=1; __host-session=2; plain=3";
+
+        assert_eq!(HeaderStringParser::new().parse::<Cookie, _>(cookie_header).filter(|c| c.is_ok()).count(), 3);
+
+        let parser = HeaderStringParser::builder().reject_prefixed_names(true).build();
+        let results: Vec<_> = parser.parse::<Cookie, _>(cookie_header).collect();
+
+        assert_eq!(
+            results[0],
+            Err(HeaderParseError::PrefixedName {
+                prefix: crate::CookiePrefix::Secure
+            })
+        );
+        assert_eq!(
+            results[1],
+            Err(HeaderParseError::PrefixedName {
+                prefix: crate::CookiePrefix::Host
+            })
+        );
+        assert_eq!(results[2].as_ref().unwrap().name(), "plain");
+    }
+
+    #[test]
+    fn custom_decoder() {
+        let parser = HeaderStringParser::builder()
+            .decoder(|value: &str| match value.strip_prefix("b64:") {
+                Some("aGk=") => Ok("hi".to_string()),
+                Some(_) => Err(ParseError::MissingPair),
+                None => Ok(value.to_string()),
+            })
+            .build();
+        let results: Vec<_> = parser.parse::<Cookie, _>("a=b64:aGk=; b=plain%20; c=b64:???").collect();
+
+        assert_eq!(results[0].as_ref().unwrap().value(), "hi");
+        assert_eq!(results[1].as_ref().unwrap().value(), "plain%20");
+        assert_eq!(
+            results[2],
+            Err(HeaderParseError::DecodeFailed {
+                raw: "b64:???".into(),
+                error: ParseError::MissingPair
+            })
+        );
+
+        let identity = HeaderStringParser::builder().decoder(crate::IdentityDecoder).build();
+        let cookies: Vec<Cookie> = identity.parse("a=x%3By").filter_map(|c| c.ok()).collect();
+        assert_eq!(cookies[0].value(), "x%3By");
+    }
+
+    #[test]
+    fn allow_valueless_tokens() {
+        let cookie_header = "bad token; a=1; secure; httponly";
+
+        let cookies: Vec<Cookie> = HeaderStringParser::new().parse(cookie_header).filter_map(|c| c.ok()).collect();
+        assert_eq!(cookies.len(), 1);
+
+        let parser = HeaderStringParser::builder().allow_valueless(true).build();
+        let cookies: Vec<Cookie> = parser.parse(cookie_header).filter_map(|c| c.ok()).collect();
+        let actual: Vec<_> = cookies.iter().map(|c| c.name_value()).collect();
+        assert_eq!(actual, [("a", "1"), ("secure", ""), ("httponly", "")]);
+    }
+
+    #[test]
+    fn parse_bytes_utf8_modes() {
+        let header = b"a=1; b=\xffx; c=\xe9t\xe9; d=4";
+
+        let cookies: Vec<Cookie> = HeaderStringParser::new().parse_bytes(header).filter_map(|c| c.ok()).collect();
+        let actual: Vec<_> = cookies.iter().map(|c| c.name_value()).collect();
+        assert_eq!(actual, [("a", "1"), ("b", "\u{FFFD}x"), ("c", "\u{FFFD}t\u{FFFD}"), ("d", "4")]);
+
+        let parser = HeaderStringParser::builder().utf8_mode(Utf8Mode::Strict).build();
+        let results: Vec<Result<Cookie, _>> = parser.parse_bytes(header).collect();
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0].as_ref().unwrap().name_value(), ("a", "1"));
+        assert!(matches!(results[1], Err(HeaderParseError::InvalidUtf8(_))));
+        assert!(matches!(results[2], Err(HeaderParseError::InvalidUtf8(_))));
+        assert_eq!(results[3].as_ref().unwrap().name_value(), ("d", "4"));
+    }
+
+    #[test]
+    fn trim_values_disabled() {
+        let parser = HeaderStringParser::builder().trim_values(false).build();
+
+        let cookies: Vec<Cookie> = parser.parse(" name=  x  ; b= y").filter_map(|c| c.ok()).collect();
+        let actual: Vec<_> = cookies.iter().map(|c| c.name_value()).collect();
+        assert_eq!(actual, [("name", "  x  "), ("b", " y")]);
+
+        let cookies: Vec<Cookie> = HeaderStringParser::new().parse("name=  x  ").filter_map(|c| c.ok()).collect();
+        assert_eq!(cookies[0].value(), "x");
+    }
+
+    #[test]
+    fn legacy_attributes() {
+        let cookie_header = "$Version=1; name=val; $Path=/; other=x;y; $Domain=\"example.com\"; $Port=80";
+        let parse = |mode| -> Vec<Cookie> {
+            let parser = HeaderStringParser::builder().legacy_attributes(mode).build();
+            parser.parse(cookie_header).filter_map(|c| c.ok()).collect()
+        };
+
+        let cookies = parse(LegacyAttributes::AsCookies);
+        let names: Vec<_> = cookies.iter().map(|c| c.name()).collect();
+        assert_eq!(names, ["$Version", "name", "other"]);
+        assert_eq!(cookies[1].value(), "val; $Path=/");
+
+        let cookies = parse(LegacyAttributes::Skip);
+        let actual: Vec<_> = cookies.iter().map(|c| (c.name_value(), c.path(), c.domain())).collect();
+        assert_eq!(actual, [(("name", "val"), None, None), (("other", "x;y"), None, None)]);
+
+        let cookies = parse(LegacyAttributes::Attach);
+        let actual: Vec<_> = cookies.iter().map(|c| (c.name_value(), c.path(), c.domain())).collect();
+        assert_eq!(actual, [(("name", "val"), Some("/"), None), (("other", "x;y"), None, Some("example.com"))]);
+
+        // Strict mode keeps the quotes in values, but not in attributes
+        let parser =
+            HeaderStringParser::builder().mode(ParseMode::Strict).legacy_attributes(LegacyAttributes::Attach).build();
+        let cookies: Vec<Cookie> = parser.parse("a=\"1\"; $Path=\"/acme\"").filter_map(|c| c.ok()).collect();
+        assert_eq!((cookies[0].value(), cookies[0].path()), ("\"1\"", Some("/acme")));
+    }
+
+    #[test]
+    fn lowercase_names() {
+        let parser = HeaderStringParser::builder().lowercase_names(true).build();
+
+        let cookies: Vec<Cookie> = parser.parse("ThÉMe=Dark; Session=1; LANG=en").filter_map(|c| c.ok()).collect();
+        let actual: Vec<_> = cookies.iter().map(|c| c.name_value()).collect();
+        assert_eq!(actual, [("thÉme", "Dark"), ("session", "1"), ("lang", "en")]);
+
+        let deduped = parser.parse::<Cookie, _>("ID=1; id=2; Id=3").dedup_last_wins();
+        assert_eq!(deduped, [("id".to_string(), "3".to_string())]);
+
+        assert_eq!(parser.parse::<Cookie, _>("a=1; ID=2").find_cookie("id").unwrap().value(), "2");
+        assert_eq!(parser.parse_pairs_owned("ID=2").next().unwrap().unwrap().0, "id");
+    }
+
+    #[test]
+    fn whitespace_only_values() {
+        let cookie_header = "a=   ; b=; c= x ; d=\t";
+        let parse = |mode| {
+            let parser = HeaderStringParser::builder().whitespace_values(mode).build();
+            let results: Vec<Result<Cookie, _>> = parser.parse(cookie_header).collect();
+            results.into_iter().map(|r| r.map(|c| c.value().to_string())).collect::<Vec<_>>()
+        };
+
+        let ok = |value: &str| Ok(value.to_string());
+        assert_eq!(parse(WhitespaceValues::Trim), [ok(""), ok(""), ok("x"), ok("")]);
+        assert_eq!(
+            parse(WhitespaceValues::Reject),
+            [Err(HeaderParseError::WhitespaceOnlyValue), ok(""), ok("x"), Err(HeaderParseError::WhitespaceOnlyValue)]
+        );
+        assert_eq!(parse(WhitespaceValues::Preserve), [ok("   "), ok(""), ok("x"), ok("\t")]);
+    }
+
+    #[test]
+    fn reject_control_chars() {
+        let cookie_header = "a=1\r\nX-Injected: 1; b=x\ty; c=\u{7f}; d=4";
+
+        let cookies: Vec<Cookie> = HeaderStringParser::new().parse(cookie_header).filter_map(|c| c.ok()).collect();
+        assert_eq!(cookies.len(), 4);
+
+        let parser = HeaderStringParser::builder().reject_control_chars(true).build();
+        let results: Vec<Result<Cookie, _>> = parser.parse(cookie_header).collect();
+        let actual: Vec<_> = results.iter().map(|r| r.as_ref().map(|c| c.name_value())).collect();
+        assert_eq!(
+            actual,
+            [
+                Err(&HeaderParseError::ControlCharacter {
+                    byte: b'\r'
+                }),
+                Ok(("b", "x\ty")),
+                Err(&HeaderParseError::ControlCharacter {
+                    byte: 0x7f
+                }),
+                Ok(("d", "4")),
+            ]
+        );
+    }
+
+    #[test]
+    fn custom_separator() {
+        let parser = HeaderStringParser::builder().separator(',').build();
+        let cookies: Vec<Cookie> = parser.parse("a=1, b=x,y; z, c=\"q,r\"").filter_map(|c| c.ok()).collect();
+        let actual: Vec<_> = cookies.iter().map(|c| c.name_value()).collect();
+        assert_eq!(actual, [("a", "1"), ("b", "x,y; z"), ("c", "q,r")]);
+
+        let parser = HeaderStringParser::builder().separator('\n').build();
+        let cookies: Vec<Cookie> = parser.parse("a=1\n\nb=2 3\nnot a cookie\nc=3").filter_map(|c| c.ok()).collect();
+        let actual: Vec<_> = cookies.iter().map(|c| c.name_value()).collect();
+        assert_eq!(actual, [("a", "1"), ("b", "2 3\nnot a cookie"), ("c", "3")]);
+    }
+
+    #[test]
+    #[should_panic(expected = "cookie separator")]
+    fn custom_separator_rejects_equals() {
+        HeaderStringParser::builder().separator('=');
+    }
+
+    #[test]
+    #[cfg(feature = "percent-encode")]
+    fn decode_only_listed_names() {
+        let cookie_header = "data=a%20b; discount=100%; label=x%20y";
+
+        let parser = HeaderStringParser::builder().decode_only(["data"]).build();
+        let cookies: Vec<Cookie> = parser.parse(cookie_header).filter_map(|c| c.ok()).collect();
+        let actual: Vec<_> = cookies.iter().map(|c| c.name_value()).collect();
+        assert_eq!(actual, [("data", "a b"), ("discount", "100%"), ("label", "x%20y")]);
+
+        let cookies: Vec<Cookie> = HeaderStringParser::new().parse(cookie_header).filter_map(|c| c.ok()).collect();
+        assert_eq!(cookies[2].value(), "x y");
+    }
+
+    #[test]
+    fn require_space_after_separator() {
+        let cookie_header = "a=x;y=1;; b=\"q; r\"; c=2;\td=3; flag;";
+        for mode in [ParseMode::Lenient, ParseMode::Strict] {
+            let parser = HeaderStringParser::builder().mode(mode).require_space_after_separator(true).build();
+            let results: Vec<Result<Cookie, _>> = parser.parse(cookie_header).collect();
+            let cookies: Vec<_> = results.iter().flatten().map(|c| c.name_value()).collect();
+
+            match mode {
+                ParseMode::Lenient => assert_eq!(cookies, [("a", "x;y=1;"), ("b", "q; r"), ("c", "2"), ("d", "3")]),
+                ParseMode::Strict => {
+                    assert_eq!(cookies, [("a", "x;y=1;"), ("b", "\"q"), ("c", "2"), ("d", "3")]);
+                    assert_eq!(results.iter().filter(|r| r.is_err()).count(), 2);
+                }
+            }
+        }
+
+        // Without the option, the heuristics split `a` at `;y=`
+        let cookies: Vec<Cookie> = HeaderStringParser::new().parse(cookie_header).filter_map(|c| c.ok()).collect();
+        assert_eq!(cookies[0].name_value(), ("a", "x"));
+    }
+
+    #[test]
+    fn decode_errors_are_distinct_from_structural_errors() {
+        let parser = HeaderStringParser::builder()
+            .mode(ParseMode::Strict)
+            .error_on_empty_name(true)
+            .decoder(|value: &str| match value {
+                "bad" => Err(ParseError::MissingPair),
+                _ => Ok(value.into()),
+            })
+            .build();
+        let results: Vec<_> = parser.parse::<Cookie, _>("flag; =x; a=bad; b=ok").collect();
+
+        assert_eq!(results[0], Err(HeaderParseError::Cookie(ParseError::MissingPair)));
+        assert_eq!(results[1], Err(HeaderParseError::Cookie(ParseError::EmptyName)));
+        assert_eq!(
+            results[2],
+            Err(HeaderParseError::DecodeFailed {
+                raw: "bad".into(),
+                error: ParseError::MissingPair
+            })
+        );
+        assert_eq!(results[3].as_ref().map(Cookie::name_value), Ok(("b", "ok")));
+
+        // The same error from the decoder is reported by every entry point
+        let pairs: Vec<_> = parser.parse_pairs_owned("a=bad").collect();
+        assert!(matches!(&pairs[0], Err(HeaderParseError::DecodeFailed { raw, .. }) if raw == "bad"));
+        let mut errors = Vec::new();
+        parser.for_each_cookie("a=bad", |_, _| {}, |err| errors.push(err));
+        assert!(matches!(&errors[0], HeaderParseError::DecodeFailed { .. }));
+        assert_eq!(
+            HeaderParseError::DecodeFailed {
+                raw: "bad".into(),
+                error: ParseError::MissingPair
+            }
+            .to_string(),
+            format!("the cookie's value could not be decoded: {}", ParseError::MissingPair)
+        );
+
+        // `Debug` redacts the raw value, also inside `Multiple`
+        let error = HeaderParseError::Multiple {
+            errors: vec![(0..5, results[2].clone().unwrap_err())],
+        };
+        assert_eq!(
+            format!("{error:?}"),
+            r#"Multiple { errors: [(0..5, DecodeFailed { raw: "***", error: MissingPair })] }"#
+        );
+    }
+
+    #[cfg(feature = "percent-encode")]
+    #[test]
+    fn plus_as_space() {
+        let parse = |parser: HeaderStringParser| -> Vec<String> {
+            parser.parse::<Cookie, _>("name=a+b; c=x%2By; d=p+q").flatten().map(|c| c.value().to_string()).collect()
+        };
+
+        assert_eq!(parse(HeaderStringParser::new()), ["a+b", "x+y", "p+q"]);
+        assert_eq!(parse(HeaderStringParser::builder().plus_as_space(true).build()), ["a b", "x+y", "p q"]);
+        assert_eq!(
+            parse(HeaderStringParser::builder().plus_as_space(true).decode_only(["name"]).build()),
+            ["a b", "x%2By", "p+q"]
+        );
+    }
+
+    #[test]
+    fn raw_on_decode_error() {
+        let cookie_header = "a=100%done; b=%FF; c=x%20y";
+        let results: Vec<Result<Cookie, _>> = HeaderStringParser::new().parse(cookie_header).collect();
+        assert_eq!(results[0].as_ref().unwrap().value(), "100%done");
+        #[cfg(feature = "percent-encode")]
+        assert!(matches!(
+            &results[1],
+            Err(HeaderParseError::DecodeFailed { raw, error: ParseError::Utf8Error(_) }) if raw == "%FF"
+        ));
+
+        let parser = HeaderStringParser::builder().raw_on_decode_error(true).build();
+        let cookies: Vec<Cookie> = parser.parse(cookie_header).filter_map(|c| c.ok()).collect();
+        let actual: Vec<_> = cookies.iter().map(|c| c.value()).collect();
+        #[cfg(feature = "percent-encode")]
+        assert_eq!(actual, ["100%done", "%FF", "x y"]);
+        #[cfg(not(feature = "percent-encode"))]
+        assert_eq!(actual, ["100%done", "%FF", "x%20y"]);
+
+        // Custom decoders fall back too
+        let parser = HeaderStringParser::builder()
+            .decoder(|value: &str| value.strip_prefix("v1:").map(str::to_string).ok_or(ParseError::MissingPair))
+            .raw_on_decode_error(true)
+            .build();
+        let cookies: Vec<Cookie> = parser.parse("a=v1:x; b=y").filter_map(|c| c.ok()).collect();
+        let actual: Vec<_> = cookies.iter().map(|c| c.value()).collect();
+        assert_eq!(actual, ["x", "y"]);
+    }
+
+    #[test]
+    fn strip_header_name() {
+        let parser = HeaderStringParser::builder().strip_header_name(true).build();
+        let parse = |header| -> Vec<(String, String)> {
+            parser.parse::<Cookie, _>(header).flatten().map(|c| (c.name().to_string(), c.value().to_string())).collect()
+        };
+
+        assert_eq!(parse("Cookie: a=1; b=2"), [("a".into(), "1".into()), ("b".into(), "2".into())]);
+        assert_eq!(parse("  set-cookie:a=1"), [("a".into(), "1".into())]);
+        assert_eq!(parse("Cookie=abc; b=x:y"), [("Cookie".into(), "abc".into()), ("b".into(), "x:y".into())]);
+
+        let cookies: Vec<Cookie> = HeaderStringParser::new().parse("Cookie: a=1").filter_map(|c| c.ok()).collect();
+        assert_eq!(cookies[0].name(), "Cookie: a");
+    }
+
+    #[test]
+    fn opaque_names() {
+        let cookie_header = "id=1; data=a=1; b=2; theme=dark";
+
+        let cookies: Vec<Cookie> = HeaderStringParser::new().parse(cookie_header).filter_map(|c| c.ok()).collect();
+        assert_eq!(cookies.len(), 4);
+
+        let parser = HeaderStringParser::builder().opaque_names(["data"]).build();
+        let cookies: Vec<Cookie> = parser.parse(cookie_header).filter_map(|c| c.ok()).collect();
+        let actual: Vec<_> = cookies.iter().map(|c| c.name_value()).collect();
+        assert_eq!(actual, [("id", "1"), ("data", "a=1; b=2; theme=dark")]);
+
+        let parser = HeaderStringParser::builder().opaque_names(["data"]).opaque_sentinel("|").build();
+        let cookies: Vec<Cookie> = parser.parse("data=a=1; b=2| theme=dark").filter_map(|c| c.ok()).collect();
+        let actual: Vec<_> = cookies.iter().map(|c| c.name_value()).collect();
+        assert_eq!(actual, [("data", "a=1; b=2"), ("theme", "dark")]);
+    }
+
+    #[test]
+    fn single_quotes() {
+        let parser = HeaderStringParser::builder().single_quotes(true).build();
+        let cases: [(&str, &[(&str, &str)]); 8] = [
+            ("name='val;ue'; b=2", &[("name", "val;ue"), ("b", "2")]),
+            ("a='x; y=z'; b=1", &[("a", "x; y=z"), ("b", "1")]),
+            ("a=''; b=2", &[("a", ""), ("b", "2")]),
+            // Mismatched quotes don't close each other and fall back to the heuristics
+            ("a='x;y\"; b=2", &[("a", "'x;y\""), ("b", "2")]),
+            ("a=\"x;y'; b=2", &[("a", "\"x;y'"), ("b", "2")]),
+            // A quote of the other type is literal inside a quoted value
+            ("a='say \"hi; there\"'; b=2", &[("a", "say \"hi; there\""), ("b", "2")]),
+            ("a=\"it's; fine\"; b=2", &[("a", "it's; fine"), ("b", "2")]),
+            ("a='it\\'s; fine'; b=2", &[("a", "it\\'s; fine"), ("b", "2")]),
+        ];
+        for (header, expected) in cases {
+            assert_name_values(&parser, header, expected);
+        }
+
+        // Without the option single quotes are ordinary value characters
+        assert_name_values(&HeaderStringParser::new(), "a='x'; b='y; c=1'", &[("a", "'x'"), ("b", "'y"), ("c", "1'")]);
+        let strict = HeaderStringParser::builder().mode(ParseMode::Strict).single_quotes(true).build();
+        assert_name_values(&strict, "a='x;y'", &[("a", "'x")]);
+    }
+
+    #[test]
+    fn name_chars() {
+        let header = "a=1; app.id=2; ns:key=3;-x=4";
+        assert_name_values(&HeaderStringParser::new(), header, &[("a", "1; app.id=2; ns:key=3"), ("-x", "4")]);
+
+        let rfc = HeaderStringParser::builder().name_chars(NameChars::RfcToken).build();
+        assert_name_values(&rfc, header, &[("a", "1"), ("app.id", "2; ns:key=3"), ("-x", "4")]);
+
+        let custom = HeaderStringParser::builder()
+            .name_chars(NameChars::Custom(|b| b.is_ascii_lowercase() || matches!(b, b'.' | b':')))
+            .build();
+        assert_name_values(&custom, header, &[("a", "1"), ("app.id", "2"), ("ns:key", "3;-x=4")]);
+
+        // Comma folding looks for the same names
+        let commas = HeaderStringParser::builder().comma_as_separator(true).name_chars(NameChars::RfcToken).build();
+        assert_name_values(&commas, "a=1, b.c=2, d:e=3", &[("a", "1"), ("b.c", "2, d:e=3")]);
+
+        assert_eq!(NameChars::RfcToken, NameChars::RfcToken);
+        assert_ne!(NameChars::Default, NameChars::RfcToken);
+    }
+
+    #[test]
+    fn comma_as_separator() {
+        let parser = HeaderStringParser::builder().comma_as_separator(true).build();
+        let cases: [(&str, &[(&str, &str)]); 8] = [
+            ("a=1, b=2; c=3", &[("a", "1"), ("b", "2"), ("c", "3")]),
+            ("a=1,b=2", &[("a", "1"), ("b", "2")]),
+            ("a=x,y; b=2", &[("a", "x,y"), ("b", "2")]),
+            ("expires=Wed, 21 Oct 2015 07:28:00 GMT, b=2", &[("expires", "Wed, 21 Oct 2015 07:28:00 GMT"), ("b", "2")]),
+            ("a=\"x, c=1\", b=2", &[("a", "x, c=1"), ("b", "2")]),
+            ("a=x;y, b=2", &[("a", "x;y"), ("b", "2")]),
+            ("a=1,, b=2", &[("a", "1,"), ("b", "2")]),
+            // A `,name=` inside a value is indistinguishable from a folded cookie
+            ("a=k1=v1,k2=v2", &[("a", "k1=v1"), ("k2", "v2")]),
+        ];
+        for (header, expected) in cases {
+            assert_name_values(&parser, header, expected);
+        }
+
+        let strict = HeaderStringParser::builder().mode(ParseMode::Strict).comma_as_separator(true).build();
+        assert_name_values(&strict, "a=1, b=x,y;c=3", &[("a", "1"), ("b", "x,y"), ("c", "3")]);
+        assert_eq!(strict.parse::<Cookie, _>("a=1, b=2, c=3").size_hint(), (0, Some(3)));
+
+        // Without the option commas are part of values
+        assert_name_values(&HeaderStringParser::new(), "a=1, b=2", &[("a", "1, b=2")]);
+    }
+
+    #[test]
+    fn max_value_equals() {
+        let cookie_header = "a=b=c; b=x; c==; d=x;y=1";
+        let parse = |parser: HeaderStringParser| -> Vec<Result<(String, String), HeaderParseError>> {
+            parser
+                .parse::<Cookie, _>(cookie_header)
+                .map(|r| r.map(|c| (c.name().to_string(), c.value().to_string())))
+                .collect()
+        };
+        let ok = |name: &str, value: &str| Ok((name.to_string(), value.to_string()));
+
+        // Everything after the first `=` is the value
+        assert_eq!(
+            parse(HeaderStringParser::new()),
+            [ok("a", "b=c"), ok("b", "x"), ok("c", "="), ok("d", "x"), ok("y", "1")]
+        );
+
+        let too_many = Err(HeaderParseError::TooManyEquals {
+            limit: 0,
+        });
+        let strict = HeaderStringParser::builder().mode(ParseMode::Strict).max_value_equals(0).build();
+        assert_eq!(parse(strict), [too_many.clone(), ok("b", "x"), too_many, ok("d", "x"), ok("y", "1")]);
+
+        // A value joined by the lookahead is checked as a whole
+        let lenient = HeaderStringParser::builder().max_value_equals(1).build();
+        let results: Vec<Result<Cookie, _>> = lenient.parse("a=x;=y; b=x;=y=z; c=p=q").collect();
+        assert_eq!(results[0].as_ref().unwrap().value(), "x;=y");
+        assert_eq!(
+            results[1],
+            Err(HeaderParseError::TooManyEquals {
+                limit: 1
+            })
+        );
+        assert_eq!(results[2].as_ref().unwrap().value(), "p=q");
+    }
+
+    #[test]
+    fn validate_cookie_octets() {
+        let parser = HeaderStringParser::builder().mode(ParseMode::Strict).validate_cookie_octets(true).build();
+        let results: Vec<Result<Cookie, _>> =
+            parser.parse("a=abc-123; b=\"quoted\"; c=x y; d=x,y; e=caf\u{e9}").collect();
+        let actual: Vec<_> = results.iter().map(|r| r.as_ref().map(|c| c.value())).collect();
+
+        assert_eq!(
+            actual,
+            [
+                Ok("abc-123"),
+                Ok("\"quoted\""),
+                Err(&HeaderParseError::InvalidCookieOctet {
+                    byte: b' '
+                }),
+                Err(&HeaderParseError::InvalidCookieOctet {
+                    byte: b','
+                }),
+                Err(&HeaderParseError::InvalidCookieOctet {
+                    byte: 0xc3
+                }),
+            ]
+        );
+    }
+}
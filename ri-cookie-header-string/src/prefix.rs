@@ -0,0 +1,49 @@
+//! Classification of cookie name prefixes with special meaning.
+
+/// A cookie name prefix that carries extra requirements when the cookie is set.
+///
+/// Browsers only accept `__Secure-` cookies that were set with the `Secure` attribute, and
+/// `__Host-` cookies that were additionally set without a `Domain` and with `Path=/`. Those
+/// attributes are not sent back in a `Cookie` request header, so a server receiving such a
+/// cookie cannot verify them itself. Matching is ASCII case-insensitive, as in browsers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CookiePrefix {
+    /// The `__Secure-` prefix.
+    Secure,
+    /// The `__Host-` prefix.
+    Host,
+}
+
+impl CookiePrefix {
+    /// Classify a cookie name by its prefix, if it has one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ri_cookie_header_string::CookiePrefix;
+    ///
+    /// assert_eq!(CookiePrefix::of("__Host-session"), Some(CookiePrefix::Host));
+    /// assert_eq!(CookiePrefix::of("__secure-id"), Some(CookiePrefix::Secure));
+    /// assert_eq!(CookiePrefix::of("session"), None);
+    /// ```
+    pub fn of(name: &str) -> Option<Self> {
+        let starts_with =
+            |prefix: &str| name.get(..prefix.len()).is_some_and(|start| start.eq_ignore_ascii_case(prefix));
+
+        if starts_with(CookiePrefix::Secure.as_str()) {
+            Some(CookiePrefix::Secure)
+        } else if starts_with(CookiePrefix::Host.as_str()) {
+            Some(CookiePrefix::Host)
+        } else {
+            None
+        }
+    }
+
+    /// Return the canonical spelling of the prefix, e.g. `__Secure-`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CookiePrefix::Secure => "__Secure-",
+            CookiePrefix::Host => "__Host-",
+        }
+    }
+}
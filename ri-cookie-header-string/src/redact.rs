@@ -0,0 +1,109 @@
+//! Redaction of cookie values in a header for logging.
+
+use crate::{HeaderStringCookies, HeaderStringParser};
+use alloc::borrow::Cow;
+use alloc::string::String;
+use cookie::Cookie;
+
+/// The text that replaces a redacted value.
+const REDACTED: &str = "***";
+
+impl HeaderStringParser {
+    /// Return `header` with the values of the cookies called one of `names` replaced by `***`,
+    /// using this parser's configuration to split it.
+    ///
+    /// Everything else, including separators, whitespace, the quotes of a quoted value and
+    /// segments that fail to parse, is kept byte for byte, so the output can be logged in place
+    /// of the original. The output is **not** length-preserving: each value is replaced by
+    /// exactly three characters whatever its length, which also hides how long the secret was,
+    /// so byte offsets after the first redacted value no longer match the original header.
+    ///
+    /// Values are located before decoding and validation, so a cookie whose value fails to
+    /// decode or exceeds a configured limit is still redacted, and `max_cookies` and
+    /// `max_header_len` are ignored so the whole header is covered. Names are compared after
+    /// [`lowercase_names`](crate::HeaderStringParserBuilder::lowercase_names) normalization,
+    /// and ignoring ASCII case with
+    /// [`case_insensitive_names`](crate::HeaderStringParserBuilder::case_insensitive_names).
+    pub fn redact(&self, header: &str, names: &[&str]) -> String {
+        let mut parser = self.clone();
+        parser.max_cookies = None;
+        parser.max_header_len = None;
+        // The cookie type is unused; the iterator only supplies ranges.
+        let mut iter = HeaderStringCookies::<Cookie>::new(Cow::Borrowed(header), parser);
+
+        let mut redacted = String::with_capacity(header.len());
+        let mut copied = 0;
+        while let Some((segment, ranges)) = iter.next_ranges() {
+            let (name, value) = match ranges {
+                Ok(ranges) => ranges,
+                // Errors carry no ranges, so fall back to splitting the segment at its first `=`
+                Err(_) => match header[segment.clone()].find('=') {
+                    Some(eq) => {
+                        let eq = segment.start + eq;
+                        (iter.trim(segment.start..eq), iter.trim(eq + 1..segment.end))
+                    }
+                    None => continue,
+                },
+            };
+
+            let name = iter.parser.normalize_name(&header[name]);
+            let case_insensitive = iter.parser.case_insensitive_names;
+            if names.iter().any(|&n| n == name || (case_insensitive && n.eq_ignore_ascii_case(&name))) {
+                redacted.push_str(&header[copied..value.start]);
+                redacted.push_str(REDACTED);
+                copied = value.end;
+            }
+        }
+
+        redacted.push_str(&header[copied..]);
+        redacted
+    }
+}
+
+/// Return `header` with the values of the cookies called one of `names` replaced by `***`,
+/// using the default configuration.
+///
+/// See [`HeaderStringParser::redact`].
+///
+/// # Example
+///
+/// ```
+/// use ri_cookie_header_string::redact;
+///
+/// let header = "session=abc;123; theme=dark; token=\"s3cr3t\"";
+///
+/// assert_eq!(redact(header, &["session", "token"]), "session=***; theme=dark; token=\"***\"");
+/// ```
+pub fn redact(header: &str, names: &[&str]) -> String {
+    HeaderStringParser::new().redact(header, names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseMode;
+
+    #[test]
+    fn redact_preserves_everything_but_values() {
+        assert_eq!(redact("", &["a"]), "");
+        assert_eq!(redact("a=1; b=2", &[]), "a=1; b=2");
+        assert_eq!(redact(" a = 1 ;b=x;y;;  c=\"q; r\" ", &["a", "b", "c"]), " a = *** ;b=***;;  c=\"***\" ");
+        assert_eq!(redact("a=1; a=2; A=3; flag; =x", &["a"]), "a=***; a=***; A=3; flag; =x");
+        assert_eq!(redact("a=; b=caf\u{e9}", &["a", "b"]), "a=***; b=***");
+    }
+
+    #[test]
+    fn redact_covers_errors_and_limits() {
+        let parser = HeaderStringParser::builder()
+            .mode(ParseMode::Strict)
+            .max_cookies(1)
+            .max_value_len(3)
+            .case_insensitive_names(true)
+            .build();
+
+        assert_eq!(
+            parser.redact("a=1; SECRET=longer value; flag; secret=x; b=%FF", &["secret", "b"]),
+            "a=1; SECRET=***; flag; secret=***; b=***"
+        );
+    }
+}
@@ -0,0 +1,211 @@
+//! Serialization of cookies back into a `Cookie` header string.
+
+use crate::{HeaderStringParser, is_token};
+use alloc::string::String;
+use cookie::Cookie;
+use core::fmt::Write;
+
+/// Check if `b` is percent-encoded in values: anything that would break re-parsing, non-ASCII
+/// bytes, plus `%` itself so that decoding the output yields the original value.
+#[inline]
+fn needs_encoding(b: u8) -> bool {
+    !b.is_ascii() || b.is_ascii_control() || matches!(b, b' ' | b'"' | b',' | b';' | b'\\' | b'%')
+}
+
+/// Check if `b` is percent-encoded in values even when encoding is off: the bytes that would
+/// split the cookie or the header line when read back.
+#[inline]
+fn needs_escaping(b: u8) -> bool {
+    b.is_ascii_control() || matches!(b, b',' | b';')
+}
+
+/// A name/value pair that can be written into a `Cookie` header.
+///
+/// Implemented for `(name, value)` tuples of string-like types and for [`Cookie`].
+pub trait CookiePair {
+    /// Return the name and value to write.
+    fn pair(&self) -> (&str, &str);
+}
+
+impl<N: AsRef<str>, V: AsRef<str>> CookiePair for (N, V) {
+    fn pair(&self) -> (&str, &str) {
+        (self.0.as_ref(), self.1.as_ref())
+    }
+}
+
+impl CookiePair for Cookie<'_> {
+    fn pair(&self) -> (&str, &str) {
+        self.name_value()
+    }
+}
+
+impl<T: CookiePair + ?Sized> CookiePair for &T {
+    fn pair(&self) -> (&str, &str) {
+        (**self).pair()
+    }
+}
+
+/// Join cookies into a `Cookie` header string, separated by `; `.
+///
+/// Cookies whose name is not an RFC 6265 token are skipped, since such a name could inject
+/// extra cookies or header lines. With the `percent-encode` feature enabled, values containing
+/// semicolons, commas, whitespace, quotes, backslashes, control characters, non-ASCII
+/// characters or `%` are percent-encoded so the output re-parses to the same values.
+/// Otherwise values are written verbatim except for semicolons, commas and control characters,
+/// which are always percent-encoded so that a value can never split the header. Use
+/// [`HeaderStringParser::to_header_string`] to choose explicitly.
+///
+/// # Example
+///
+/// ```
+/// use ri_cookie_header_string::to_header_string;
+///
+/// let header = to_header_string([("session", "abc"), ("theme", "dark")]);
+///
+/// assert_eq!(header, "session=abc; theme=dark");
+/// ```
+pub fn to_header_string<I>(cookies: I) -> String
+where
+    I: IntoIterator,
+    I::Item: CookiePair,
+{
+    write_header(cookies, cfg!(feature = "percent-encode"))
+}
+
+impl HeaderStringParser {
+    /// Join cookies into a `Cookie` header string, separated by `; `, percent-encoding values
+    /// according to this parser's
+    /// [`encode_on_serialize`](crate::HeaderStringParserBuilder::encode_on_serialize) option.
+    ///
+    /// See [`to_header_string`].
+    pub fn to_header_string<I>(&self, cookies: I) -> String
+    where
+        I: IntoIterator,
+        I::Item: CookiePair,
+    {
+        write_header(cookies, self.encode_on_serialize.unwrap_or(cfg!(feature = "percent-encode")))
+    }
+}
+
+fn write_header<I>(cookies: I, encode: bool) -> String
+where
+    I: IntoIterator,
+    I::Item: CookiePair,
+{
+    let mut header = String::new();
+
+    for cookie in cookies {
+        let (name, value) = cookie.pair();
+        if !is_token(name) {
+            continue;
+        }
+
+        if !header.is_empty() {
+            header.push_str("; ");
+        }
+        header.push_str(name);
+        header.push('=');
+
+        let escape = if encode {
+            needs_encoding
+        } else {
+            needs_escaping
+        };
+        let mut buf = [0; 4];
+        for c in value.chars() {
+            let bytes = c.encode_utf8(&mut buf).as_bytes();
+            // Escaping is decided by the first byte, so multi-byte characters are kept whole
+            if escape(bytes[0]) {
+                for b in bytes {
+                    // Writing to a `String` never fails
+                    let _ = write!(header, "%{b:02X}");
+                }
+            } else {
+                header.push(c);
+            }
+        }
+    }
+
+    header
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CookieHeaderStringExt;
+
+    #[test]
+    fn to_header_string_pairs() {
+        assert_eq!(to_header_string(Vec::<(&str, &str)>::new()), "");
+        assert_eq!(to_header_string([("a", "")]), "a=");
+        assert_eq!(to_header_string([("session-id", "1"), ("b", "2")]), "session-id=1; b=2");
+    }
+
+    #[test]
+    fn to_header_string_cookies() {
+        let cookies: Vec<_> = Cookie::header_string_parse("a=1; b=x;y z").filter_map(|c| c.ok()).collect();
+
+        #[cfg(not(feature = "percent-encode"))]
+        assert_eq!(to_header_string(&cookies), "a=1; b=x%3By z");
+        #[cfg(feature = "percent-encode")]
+        assert_eq!(to_header_string(&cookies), "a=1; b=x%3By%20z");
+    }
+
+    #[test]
+    fn to_header_string_skips_unsafe_names() {
+        let pairs =
+            [("a", "1"), ("b; admin", "1"), ("c\r\nX-Admin: 1", "2"), ("", "3"), ("caf\u{e9}", "4"), ("d", "5")];
+
+        for encode in [false, true] {
+            let parser = HeaderStringParser::builder().encode_on_serialize(encode).build();
+            assert_eq!(parser.to_header_string(pairs), "a=1; d=5");
+        }
+
+        // A decoded value can't inject a cookie either
+        let parser = HeaderStringParser::builder().encode_on_serialize(false).build();
+        assert_eq!(parser.to_header_string([("a", "x; admin=1")]), "a=x%3B admin=1");
+    }
+
+    #[test]
+    fn encode_on_serialize() {
+        let pairs = [("a", "x;y"), ("b", "é\r\n")];
+
+        let parser = HeaderStringParser::builder().encode_on_serialize(true).build();
+        assert_eq!(parser.to_header_string(pairs), "a=x%3By; b=%C3%A9%0D%0A");
+
+        // Delimiters and control characters are escaped even with encoding off
+        let parser = HeaderStringParser::builder().encode_on_serialize(false).build();
+        assert_eq!(parser.to_header_string(pairs), "a=x%3By; b=é%0D%0A");
+        assert_eq!(parser.to_header_string([("a", "1,%2 \"q\"\t")]), "a=1%2C%2 \"q\"%09");
+
+        assert_eq!(HeaderStringParser::new().to_header_string(pairs), to_header_string(pairs));
+    }
+
+    #[test]
+    #[cfg(feature = "percent-encode")]
+    fn encode_on_serialize_round_trip() {
+        // Parse lenient input, then emit output that even a strict parser splits correctly
+        let cookies: Vec<_> =
+            Cookie::header_string_parse("session=abc;123; theme=dark").filter_map(|c| c.ok()).collect();
+        let parser = HeaderStringParser::builder().encode_on_serialize(true).build();
+        let header = parser.to_header_string(&cookies);
+        assert_eq!(header, "session=abc%3B123; theme=dark");
+
+        let strict = HeaderStringParser::builder().mode(crate::ParseMode::Strict).build();
+        let reparsed: Vec<Cookie> = strict.parse(header).filter_map(|c| c.ok()).collect();
+        assert_eq!(reparsed, cookies);
+    }
+
+    #[test]
+    #[cfg(feature = "percent-encode")]
+    fn to_header_string_round_trip() {
+        let pairs = [("a", "100%"), ("b", "x; y=z"), ("c", "")];
+        let header = to_header_string(pairs);
+
+        assert_eq!(header, "a=100%25; b=x%3B%20y=z; c=");
+
+        let cookies: Vec<_> = Cookie::header_string_parse(header).filter_map(|c| c.ok()).collect();
+        let actual: Vec<_> = cookies.iter().map(|c| c.name_value()).collect();
+        assert_eq!(actual, pairs);
+    }
+}
@@ -0,0 +1,248 @@
+//! Parsing of `Set-Cookie` header values with attributes.
+
+use crate::HeaderParseError;
+use alloc::format;
+use alloc::string::ToString;
+use cookie::{Cookie, ParseError};
+
+/// Attribute names recognized in a `Set-Cookie` header value, in lowercase.
+const ATTRIBUTES: &[&str] = &["expires", "max-age", "domain", "path", "secure", "httponly", "samesite", "partitioned"];
+
+/// Parse a single `Set-Cookie` header value into a cookie with its attributes populated.
+///
+/// The `name=value` pair extends up to the first semicolon that is followed by a known
+/// cookie attribute (`Path`, `Domain`, `Expires`, `Max-Age`, `Secure`, `HttpOnly`, `SameSite`
+/// or `Partitioned`), so unquoted semicolons inside the value are preserved. Everything after
+/// that point is handed to [`Cookie::parse`] for the attribute grammar, so
+/// [`same_site`](Cookie::same_site), [`secure`](Cookie::secure),
+/// [`http_only`](Cookie::http_only), [`max_age`](Cookie::max_age),
+/// [`expires`](Cookie::expires) and the other accessors behave exactly as for a cookie parsed
+/// by the `cookie` crate. The value itself is taken verbatim and is not percent-decoded.
+///
+/// # Example
+///
+/// ```
+/// use ri_cookie_header_string::parse_set_cookie;
+///
+/// let cookie = parse_set_cookie("session=abc;123; Path=/; Secure; HttpOnly").unwrap();
+///
+/// assert_eq!(cookie.value(), "abc;123");
+/// assert_eq!(cookie.path(), Some("/"));
+/// assert_eq!(cookie.secure(), Some(true));
+/// assert_eq!(cookie.http_only(), Some(true));
+/// ```
+pub fn parse_set_cookie(string: &str) -> Result<Cookie<'static>, HeaderParseError> {
+    let s = string.trim();
+    let (pair, attributes) = s.split_at(find_attributes_start(s));
+
+    let eq_pos = pair.find('=').ok_or(ParseError::MissingPair)?;
+    let name = pair[..eq_pos].trim();
+    let value = pair[eq_pos + 1..].trim();
+
+    if name.is_empty() {
+        return Err(ParseError::EmptyName.into());
+    }
+
+    // The `cookie` crate would cut the value at its first semicolon, so let it parse the
+    // attributes with an empty value and set the real value afterwards
+    let mut cookie = Cookie::parse(format!("{name}={attributes}"))?.into_owned();
+    cookie.set_value(value.to_string());
+
+    Ok(cookie)
+}
+
+/// Parse several `Set-Cookie` header values joined into one string, as returned by APIs that
+/// fold repeated headers with `,` or put each value on its own line.
+///
+/// The string is split at every newline and at every comma that is followed by the start of a
+/// new cookie: a name made of ASCII letters, digits, `_` and `-`, then `=`, where the name is
+/// not a known cookie attribute. Commas inside attributes, like the one in
+/// `Expires=Wed, 21 Oct 2015 07:28:00 GMT`, and semicolons never split cookies. Each piece is
+/// parsed with [`parse_set_cookie`] after trimming the whitespace and semicolons left around
+/// the comma, and blank pieces are skipped.
+///
+/// A value that contains `,name=` is indistinguishable from the start of a new cookie and is
+/// split there.
+///
+/// # Example
+///
+/// ```
+/// use ri_cookie_header_string::parse_set_cookie_list;
+///
+/// let joined = "id=a;b; Expires=Wed, 21 Oct 2015 07:28:00 GMT; Secure, theme=dark; Path=/";
+/// let cookies: Vec<_> = parse_set_cookie_list(joined).collect::<Result<_, _>>().unwrap();
+///
+/// assert_eq!(cookies.len(), 2);
+/// assert_eq!(cookies[0].value(), "a;b");
+/// assert_eq!(cookies[0].secure(), Some(true));
+/// assert_eq!(cookies[1].name_value(), ("theme", "dark"));
+/// assert_eq!(cookies[1].path(), Some("/"));
+/// ```
+pub fn parse_set_cookie_list(string: &str) -> SetCookieList<'_> {
+    SetCookieList {
+        rest: string,
+    }
+}
+
+/// Iterator over the cookies of joined `Set-Cookie` header values.
+///
+/// Created by [`parse_set_cookie_list`].
+#[derive(Debug, Clone)]
+pub struct SetCookieList<'a> {
+    rest: &'a str,
+}
+
+impl Iterator for SetCookieList<'_> {
+    type Item = Result<Cookie<'static>, HeaderParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.rest.is_empty() {
+            let end = find_cookie_end(self.rest);
+            let segment = &self.rest[..end];
+            self.rest = self.rest.get(end + 1..).unwrap_or_default();
+
+            let segment = segment.trim_matches(|c: char| c == ';' || c.is_whitespace());
+            if !segment.is_empty() {
+                return Some(parse_set_cookie(segment));
+            }
+        }
+        None
+    }
+}
+
+/// Find the index of the newline or comma that ends the first cookie, or the string length.
+fn find_cookie_end(s: &str) -> usize {
+    let mut offset = 0;
+
+    while let Some(k) = s[offset..].find([',', '\n']) {
+        let pos = offset + k;
+        if s.as_bytes()[pos] == b'\n' || starts_cookie(&s[pos + 1..]) {
+            return pos;
+        }
+        offset = pos + 1;
+    }
+
+    s.len()
+}
+
+/// Check if `s` starts, after optional whitespace, with a `name=` that isn't an attribute.
+fn starts_cookie(s: &str) -> bool {
+    let s = s.trim_start();
+    let name_len = s.bytes().take_while(|b| b.is_ascii_alphanumeric() || matches!(b, b'_' | b'-')).count();
+    let name = &s[..name_len];
+
+    name_len > 0
+        && s[name_len..].trim_start().starts_with('=')
+        && !ATTRIBUTES.iter().any(|known| name.eq_ignore_ascii_case(known))
+}
+
+/// Find the index of the semicolon that starts the attribute list, or the string length.
+fn find_attributes_start(s: &str) -> usize {
+    let mut offset = 0;
+
+    while let Some(k) = s[offset..].find(';') {
+        let pos = offset + k;
+        let rest = s[pos + 1..].trim_start();
+        let end = rest.find(['=', ';']).unwrap_or(rest.len());
+        let attribute = rest[..end].trim();
+
+        if ATTRIBUTES.iter().any(|known| attribute.eq_ignore_ascii_case(known)) {
+            return pos;
+        }
+
+        offset = pos + 1;
+    }
+
+    s.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cookie::SameSite;
+
+    #[test]
+    fn parse_set_cookie_attributes() {
+        let cookie =
+            parse_set_cookie("id=a3fWa; Expires=Wed, 21 Oct 2015 07:28:00 GMT; Domain=example.com; Max-Age=60")
+                .unwrap();
+
+        assert_eq!(cookie.name_value(), ("id", "a3fWa"));
+        assert_eq!(cookie.domain(), Some("example.com"));
+        assert_eq!(cookie.max_age(), Some(cookie::time::Duration::seconds(60)));
+        assert!(cookie.expires().is_some());
+    }
+
+    #[test]
+    fn parse_set_cookie_flags_and_same_site() {
+        let cookie =
+            parse_set_cookie("session=abc;123; SameSite=Strict; Secure; HttpOnly; Max-Age=0; Partitioned").unwrap();
+
+        assert_eq!(cookie.value(), "abc;123");
+        assert_eq!(cookie.same_site(), Some(SameSite::Strict));
+        assert_eq!(cookie.secure(), Some(true));
+        assert_eq!(cookie.http_only(), Some(true));
+        assert_eq!(cookie.max_age(), Some(cookie::time::Duration::ZERO));
+        assert_eq!(cookie.partitioned(), Some(true));
+
+        let cookie = parse_set_cookie("a=1; samesite=lax").unwrap();
+        assert_eq!(cookie.same_site(), Some(SameSite::Lax));
+        assert_eq!(cookie.secure(), None);
+
+        // Unknown SameSite values are handled exactly like in `Cookie::parse`
+        let cookie = parse_set_cookie("a=1; SameSite=Sometimes").unwrap();
+        assert_eq!(cookie.same_site(), Cookie::parse("a=1; SameSite=Sometimes").unwrap().same_site());
+    }
+
+    #[test]
+    fn parse_set_cookie_semicolons_in_value() {
+        let cookie = parse_set_cookie("data=a;b;c=d; path=/app").unwrap();
+
+        assert_eq!(cookie.value(), "a;b;c=d");
+        assert_eq!(cookie.path(), Some("/app"));
+
+        let cookie = parse_set_cookie("data=a;b").unwrap();
+        assert_eq!(cookie.value(), "a;b");
+        assert_eq!(cookie.path(), None);
+    }
+
+    #[test]
+    fn parse_set_cookie_list_splits_at_cookie_boundaries() {
+        let joined = "a=1; Expires=Wed, 21 Oct 2015 07:28:00 GMT; Path=/, b=x;y; Expires=Thu, 22 Oct 2015 07:28:00 \
+                      GMT;, c=3; Secure,d=4\ne=5; Max-Age=60";
+        let cookies: Vec<_> = parse_set_cookie_list(joined).collect::<Result<_, _>>().unwrap();
+        let pairs: Vec<_> = cookies.iter().map(Cookie::name_value).collect();
+
+        assert_eq!(pairs, [("a", "1"), ("b", "x;y"), ("c", "3"), ("d", "4"), ("e", "5")]);
+        assert_eq!(cookies[0].path(), Some("/"));
+        assert_eq!(cookies[0].expires_datetime().map(|t| t.day()), Some(21));
+        assert_eq!(cookies[1].expires_datetime().map(|t| t.day()), Some(22));
+        assert_eq!(cookies[2].secure(), Some(true));
+        assert_eq!(cookies[4].max_age(), Some(cookie::time::Duration::seconds(60)));
+
+        // Commas not followed by a new cookie stay in the value or attributes
+        let cookies: Vec<_> = parse_set_cookie_list("a=x,y; Path=/a,b, Max-Age=1").collect();
+        assert_eq!(cookies.len(), 1);
+        let cookie = cookies[0].as_ref().unwrap();
+        assert_eq!(cookie.value(), "x,y");
+        assert_eq!(cookie.path(), Some("/a,b, Max-Age=1"));
+    }
+
+    #[test]
+    fn parse_set_cookie_list_blanks_and_errors() {
+        assert_eq!(parse_set_cookie_list("").count(), 0);
+        assert_eq!(parse_set_cookie_list(" ;\n\n").count(), 0);
+
+        let results: Vec<_> = parse_set_cookie_list("a=1\nflag; Secure\n=x, b=2").collect();
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[1], Err(HeaderParseError::Cookie(ParseError::MissingPair)));
+        assert_eq!(results[2], Err(HeaderParseError::Cookie(ParseError::EmptyName)));
+        assert_eq!(results[3].as_ref().map(Cookie::name_value), Ok(("b", "2")));
+    }
+
+    #[test]
+    fn parse_set_cookie_errors() {
+        assert_eq!(parse_set_cookie("novalue; Path=/"), Err(HeaderParseError::Cookie(ParseError::MissingPair)));
+        assert_eq!(parse_set_cookie("=value; Secure"), Err(HeaderParseError::Cookie(ParseError::EmptyName)));
+    }
+}
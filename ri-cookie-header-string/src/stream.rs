@@ -0,0 +1,339 @@
+//! Parsing of a cookie header that arrives as an asynchronous stream of chunks.
+
+use crate::{CookieBuilder, HeaderParseError, HeaderStringCookies, HeaderStringParser, ParseMode, skip_whitespace};
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use cookie::Cookie;
+use core::ops::Range;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_core::Stream;
+
+/// Stream of cookies parsed from a cookie header that arrives in chunks of bytes.
+///
+/// Yields the same items, in the same order, as parsing the whole header at once with the same
+/// parser would. Created by [`HeaderStringParser::parse_stream`] or [`parse_stream`].
+///
+/// # Buffering
+///
+/// The lenient heuristics decide whether a separator ends a cookie by looking at what follows
+/// it, so a cookie is only yielded once its end no longer depends on data still to come: once
+/// a later separator has arrived, since the built-in lookahead never looks past the next
+/// separator, or once the inner stream ends. A double-quoted value is also held back until its
+/// closing quote has arrived. Until then the unfinished tail of the header is kept in a buffer,
+/// which grows with the longest cookie rather than with the whole header. The tail is rescanned
+/// whenever a chunk containing a separator arrives.
+///
+/// A custom [`BoundaryStrategy`](crate::BoundaryStrategy) that looks past the next separator
+/// only sees the data received so far.
+///
+/// # Backpressure
+///
+/// The inner stream is only polled when no parsed cookie is waiting to be yielded, so a slow
+/// consumer slows down reading the header. [`max_header_len`] limits the total number of bytes
+/// received: once it is exceeded the stream yields [`HeaderParseError::HeaderTooLong`] and ends
+/// without buffering more, although cookies completed before that point have already been
+/// yielded.
+///
+/// The inner stream must be [`Unpin`]; pin it with `Box::pin` otherwise.
+///
+/// [`max_header_len`]: crate::HeaderStringParserBuilder::max_header_len
+pub struct CookieStream<St, C> {
+    stream: St,
+    // The parser used for each scan, without a header length limit.
+    parser: HeaderStringParser,
+    // The header length limit, checked against the total number of bytes received.
+    max_header_len: Option<usize>,
+    // The received bytes that haven't been parsed into items yet.
+    buffer: Vec<u8>,
+    received: usize,
+    // The number of cookies produced so far, carried across scans for `max_cookies`.
+    emitted: usize,
+    // Items parsed but not yet yielded.
+    ready: VecDeque<Result<C, HeaderParseError>>,
+    finished: bool,
+}
+
+impl<St, C> CookieStream<St, C>
+where
+    C: CookieBuilder<'static>,
+{
+    fn new(stream: St, mut parser: HeaderStringParser) -> Self {
+        let max_header_len = parser.max_header_len.take();
+        CookieStream {
+            stream,
+            parser,
+            max_header_len,
+            buffer: Vec::new(),
+            received: 0,
+            emitted: 0,
+            ready: VecDeque::new(),
+            finished: false,
+        }
+    }
+
+    /// Add a chunk to the buffer and parse the cookies it completes.
+    fn receive(&mut self, chunk: &[u8]) {
+        self.received += chunk.len();
+        if let Some(limit) = self.max_header_len.filter(|&max| self.received > max) {
+            self.buffer = Vec::new();
+            self.ready.push_back(Err(HeaderParseError::HeaderTooLong {
+                limit,
+            }));
+            self.finished = true;
+            return;
+        }
+
+        self.buffer.extend_from_slice(chunk);
+        // Only a new separator can complete a cookie
+        if chunk.contains(&self.parser.separator()) {
+            self.scan();
+        }
+    }
+
+    /// Parse the items whose segments are decided by the separators received so far.
+    fn scan(&mut self) {
+        let Some(last_sep) = self.buffer.iter().rposition(|&b| b == self.parser.separator()) else {
+            return;
+        };
+
+        // Deciding the earlier separators needs no lookahead past the last one
+        let prefix = self.buffer[..=last_sep].to_vec();
+        let mut iter = HeaderStringCookies::<C>::from_owned_bytes(prefix, self.parser.clone());
+        iter.emitted = self.emitted;
+        let last_sep = iter.string.len() - 1;
+        let mut consumed = 0;
+
+        while let Some((segment, ranges)) = iter.next_ranges() {
+            let terminal = matches!(ranges, Err(HeaderParseError::TooManyCookies { .. }));
+            if !terminal && (iter.last > last_sep || self.opens_unclosed_quote(iter.string.as_bytes(), segment)) {
+                break;
+            }
+
+            let item = iter.build_item(ranges);
+            self.ready.push_back(item);
+            if terminal {
+                self.buffer = Vec::new();
+                self.finished = true;
+                return;
+            }
+            consumed = iter.last;
+        }
+
+        self.emitted = iter.emitted;
+        let consumed = source_offset(&self.buffer, consumed);
+        self.buffer.drain(..consumed);
+    }
+
+    /// Parse the rest of the buffer once the inner stream has ended.
+    fn finish(&mut self) {
+        let mut iter =
+            HeaderStringCookies::<C>::from_owned_bytes(core::mem::take(&mut self.buffer), self.parser.clone());
+        iter.emitted = self.emitted;
+        self.ready.extend(iter);
+        self.finished = true;
+    }
+
+    /// Check if the value of `segment` opens a quote that isn't closed within `bytes`,
+    /// in which case the value may run past the end of the segment.
+    fn opens_unclosed_quote(&self, bytes: &[u8], segment: Range<usize>) -> bool {
+        if self.parser.mode == ParseMode::Strict {
+            return false;
+        }
+        let Some(eq) = bytes[segment.clone()].iter().position(|&b| b == b'=') else {
+            return false;
+        };
+
+        let start = skip_whitespace(bytes, segment.start + eq + 1, self.parser.separator());
+        let quote = bytes.get(start).copied();
+        let quoted = quote == Some(b'"') || (self.parser.single_quotes && quote == Some(b'\''));
+        quoted && self.parser.find_closing_quote(bytes, start).is_none()
+    }
+}
+
+// Parsed items are never pinned, so only the inner stream needs to be `Unpin`
+impl<St: Unpin, C> Unpin for CookieStream<St, C> {}
+
+impl<St, C> Stream for CookieStream<St, C>
+where
+    St: Stream + Unpin,
+    St::Item: AsRef<[u8]>,
+    C: CookieBuilder<'static>,
+{
+    type Item = Result<C, HeaderParseError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Some(item) = this.ready.pop_front() {
+                return Poll::Ready(Some(item));
+            }
+            if this.finished {
+                return Poll::Ready(None);
+            }
+
+            match Pin::new(&mut this.stream).poll_next(cx) {
+                Poll::Ready(Some(chunk)) => this.receive(chunk.as_ref()),
+                Poll::Ready(None) => this.finish(),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// Map an offset in the string decoded from `bytes` back to an offset in `bytes`.
+///
+/// The two only differ when invalid UTF-8 sequences were replaced with `U+FFFD`. `offset` must
+/// not fall inside a replacement, which holds for offsets just past a separator.
+fn source_offset(bytes: &[u8], mut offset: usize) -> usize {
+    let mut source = 0;
+    for chunk in bytes.utf8_chunks() {
+        let valid = chunk.valid().len();
+        if offset <= valid {
+            return source + offset;
+        }
+        offset -= valid;
+        source += valid;
+
+        if !chunk.invalid().is_empty() {
+            offset = offset.saturating_sub(char::REPLACEMENT_CHARACTER.len_utf8());
+            source += chunk.invalid().len();
+        }
+    }
+    source
+}
+
+impl HeaderStringParser {
+    /// Parse a cookie header that arrives as a stream of byte chunks using this parser's
+    /// configuration.
+    ///
+    /// Invalid UTF-8 is handled like in [`parse_bytes`](Self::parse_bytes). See
+    /// [`CookieStream`] for how chunks are buffered.
+    pub fn parse_stream<St, C>(&self, stream: St) -> CookieStream<St, C>
+    where
+        St: Stream + Unpin,
+        St::Item: AsRef<[u8]>,
+        C: CookieBuilder<'static>,
+    {
+        CookieStream::new(stream, self.clone())
+    }
+}
+
+/// Parse a cookie header that arrives as a stream of byte chunks using the default
+/// configuration.
+///
+/// See [`CookieStream`] for how chunks are buffered.
+pub fn parse_stream<St>(stream: St) -> CookieStream<St, Cookie<'static>>
+where
+    St: Stream + Unpin,
+    St::Item: AsRef<[u8]>,
+{
+    HeaderStringParser::new().parse_stream(stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::{String, ToString};
+    use alloc::vec;
+    use core::task::Waker;
+
+    /// A stream of chunks that is pending before every chunk.
+    struct Chunks {
+        chunks: VecDeque<Vec<u8>>,
+        pending: bool,
+    }
+
+    impl Stream for Chunks {
+        type Item = Vec<u8>;
+
+        fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Vec<u8>>> {
+            self.pending = !self.pending;
+            if self.pending {
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            Poll::Ready(self.chunks.pop_front())
+        }
+    }
+
+    /// Poll `stream` to completion, summarizing each item.
+    fn collect<St: Stream<Item = Result<Cookie<'static>, HeaderParseError>> + Unpin>(
+        mut stream: St,
+    ) -> Vec<Result<(String, String), HeaderParseError>> {
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut items = Vec::new();
+        loop {
+            match Pin::new(&mut stream).poll_next(&mut cx) {
+                Poll::Ready(Some(item)) => items.push(item.map(|c| (c.name().to_string(), c.value().to_string()))),
+                Poll::Ready(None) => return items,
+                Poll::Pending => {}
+            }
+        }
+    }
+
+    fn chunks(header: &[u8], splits: &[usize]) -> Chunks {
+        let mut chunks = VecDeque::new();
+        let mut start = 0;
+        for &split in splits.iter().chain([&header.len()]) {
+            chunks.push_back(header[start..split].to_vec());
+            start = split;
+        }
+        Chunks {
+            chunks,
+            pending: false,
+        }
+    }
+
+    #[test]
+    fn stream_matches_whole_header() {
+        let strict = HeaderStringParser::builder().mode(ParseMode::Strict).build();
+        let limited = HeaderStringParser::builder().max_cookies(2).build();
+        let single_quotes = HeaderStringParser::builder().single_quotes(true).build();
+        let headers: [&[u8]; 8] = [
+            b"a=1; b=2; c=3",
+            b"session=abc;123; b=x;y;z; c=3",
+            b"a=\"x; b=1; c\"; d=2; e=\"q;r\"",
+            b";; flag; =x; a=1;",
+            "a=caf\u{e9}; b=\u{1f36a};c".as_bytes(),
+            b"a=\xff\xfe; b=2; c=\xe2\x82",
+            b"a=1\r\n",
+            b"a='x; b=1'; c='y",
+        ];
+
+        for parser in [HeaderStringParser::new(), strict, limited, single_quotes] {
+            for header in headers {
+                let expected: Vec<_> = parser
+                    .parse_bytes::<Cookie>(header)
+                    .map(|r| r.map(|c| (c.name().to_string(), c.value().to_string())))
+                    .collect();
+
+                for i in 0..=header.len() {
+                    for j in i..=header.len() {
+                        let actual = collect(parser.parse_stream(chunks(header, &[i, j])));
+                        assert_eq!(actual, expected, "{header:?} split at {i} and {j} with {parser:?}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn stream_limits_header_len() {
+        let parser = HeaderStringParser::builder().max_header_len(10).build();
+        let actual = collect(parser.parse_stream(chunks(b"a=1; b=2; c=3", &[5, 10])));
+
+        assert_eq!(
+            actual,
+            [
+                Ok(("a".to_string(), "1".to_string())),
+                Err(HeaderParseError::HeaderTooLong {
+                    limit: 10
+                })
+            ]
+        );
+        assert_eq!(collect(parse_stream(chunks(b"a=1", &[]))), [Ok(("a".to_string(), "1".to_string()))]);
+        assert_eq!(collect(parse_stream(chunks(b"", &[]))), vec![]);
+    }
+}
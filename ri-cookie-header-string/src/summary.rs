@@ -0,0 +1,151 @@
+//! Loggable summaries of a parsed header.
+
+use crate::{CookieBuilder, HeaderParseError, HeaderStringCookies, NameValue};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+/// A summary of a fully parsed header, meant for logging.
+///
+/// `Display` prints the counts, e.g. `3 cookies, 1 errors`. `Debug` lists the cookie names and
+/// the errors but redacts the values, so a summary can be logged without leaking session tokens
+/// or other secrets. Call [`with_values`](Self::with_values) to opt in to printing values. The
+/// raw value of a [`DecodeFailed`](HeaderParseError::DecodeFailed) error is redacted either way,
+/// like in the error's own `Debug` output.
+///
+/// Created by [`HeaderStringCookies::summarize`].
+///
+/// # Example
+///
+/// ```
+/// use cookie::Cookie;
+/// use ri_cookie_header_string::CookieHeaderStringExt;
+///
+/// let summary = Cookie::header_string_parse("session=secret; theme=dark").summarize();
+///
+/// assert_eq!(summary.to_string(), "2 cookies, 0 errors");
+/// assert_eq!(format!("{summary:?}"), r#"ParseSummary { names: ["session", "theme"], errors: [] }"#);
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct ParseSummary {
+    cookies: Vec<(String, String)>,
+    errors: Vec<HeaderParseError>,
+    show_values: bool,
+}
+
+impl ParseSummary {
+    /// Include cookie values in the `Debug` output.
+    ///
+    /// Only use this where logs cannot leak secrets, e.g. in local debugging.
+    pub fn with_values(mut self) -> Self {
+        self.show_values = true;
+        self
+    }
+
+    /// Return the number of cookies parsed successfully.
+    pub fn cookie_count(&self) -> usize {
+        self.cookies.len()
+    }
+
+    /// Return the number of errors encountered.
+    pub fn error_count(&self) -> usize {
+        self.errors.len()
+    }
+
+    /// Return the names of the cookies parsed successfully, in order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.cookies.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Return the errors encountered, in order.
+    pub fn errors(&self) -> &[HeaderParseError] {
+        &self.errors
+    }
+}
+
+impl fmt::Display for ParseSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} cookies, {} errors", self.cookie_count(), self.error_count())
+    }
+}
+
+impl fmt::Debug for ParseSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("ParseSummary");
+
+        if self.show_values {
+            debug.field("cookies", &self.cookies);
+        } else {
+            debug.field("names", &self.names().collect::<Vec<_>>());
+        }
+        debug.field("errors", &self.errors).finish()
+    }
+}
+
+impl<'c, C: CookieBuilder<'c> + NameValue> HeaderStringCookies<'c, C> {
+    /// Drain the iterator into a [`ParseSummary`] suitable for logging.
+    pub fn summarize(self) -> ParseSummary {
+        let mut cookies = Vec::new();
+        let mut errors = Vec::new();
+
+        for result in self {
+            match result {
+                Ok(cookie) => {
+                    let (name, value) = cookie.name_value();
+                    cookies.push((name.to_string(), value.to_string()));
+                }
+                Err(err) => errors.push(err),
+            }
+        }
+
+        ParseSummary {
+            cookies,
+            errors,
+            show_values: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{HeaderStringParser, ParseMode};
+    use alloc::string::String;
+    use cookie::{Cookie, ParseError};
+
+    #[test]
+    fn summary_redacts_values() {
+        let parser = HeaderStringParser::builder().mode(ParseMode::Strict).build();
+        let summary = parser.parse::<Cookie, _>("session=s3cr3t; bad; id=42").summarize();
+
+        assert_eq!(summary.to_string(), "2 cookies, 1 errors");
+        assert_eq!(summary.names().collect::<Vec<_>>(), ["session", "id"]);
+
+        let redacted = format!("{summary:?}");
+        assert!(!redacted.contains("s3cr3t"));
+        assert!(redacted.contains("MissingPair"));
+
+        let revealed = format!("{:?}", summary.with_values());
+        assert!(revealed.contains("s3cr3t"));
+
+        // The raw value of a value that fails to decode is redacted too
+        let parser = HeaderStringParser::builder()
+            .decoder(|value: &str| {
+                if value.contains('%') {
+                    Err(ParseError::MissingPair)
+                } else {
+                    Ok(String::from(value))
+                }
+            })
+            .build();
+        let summary = parser.parse::<Cookie, _>("session=s3cr3t%FF; a=1").summarize();
+        assert_eq!(summary.to_string(), "1 cookies, 1 errors");
+
+        let redacted = format!("{summary:?}");
+        assert!(!redacted.contains("s3cr3t"));
+        assert!(redacted.contains("DecodeFailed"));
+
+        let revealed = format!("{:?}", summary.with_values());
+        assert!(!revealed.contains("s3cr3t"));
+        assert!(revealed.contains(r#"raw: "***""#));
+    }
+}
@@ -0,0 +1,276 @@
+//! Validation and counting of a header, and all-or-nothing parsing.
+
+use crate::{CookieBuilder, HeaderParseError, HeaderStringCookies, HeaderStringParser, ParseMode};
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use cookie::Cookie;
+
+impl HeaderStringParser {
+    /// Check a header against this parser's configuration, returning the number of cookies it
+    /// contains or the first error.
+    ///
+    /// The header is scanned exactly as [`parse`](Self::parse) would, applying every configured
+    /// limit and validation, but no cookie is constructed and no value is copied or decoded, so
+    /// this is the cheapest way to enforce a policy before forwarding the original header
+    /// untouched. Because values aren't decoded, errors from the [`ValueDecoder`] can't be
+    /// detected. Scanning stops at the first error, unless
+    /// [`collect_all_errors`](crate::HeaderStringParserBuilder::collect_all_errors) is enabled.
+    ///
+    /// [`ValueDecoder`]: crate::ValueDecoder
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ri_cookie_header_string::{HeaderParseError, HeaderStringParser};
+    ///
+    /// let parser = HeaderStringParser::builder().max_cookies(2).build();
+    ///
+    /// assert_eq!(parser.validate("a=1; b=x;y"), Ok(2));
+    /// assert_eq!(parser.validate("a=1; b=2; c=3"), Err(HeaderParseError::TooManyCookies { limit: 2 }));
+    /// ```
+    pub fn validate(&self, header: &str) -> Result<usize, HeaderParseError> {
+        // The cookie type is unused; the iterator only supplies ranges.
+        let mut iter = HeaderStringCookies::<Cookie>::new(Cow::Borrowed(header), self.clone());
+        let mut errors = Vec::new();
+
+        while let Some((segment, ranges)) = iter.next_ranges() {
+            match ranges {
+                Ok(_) => iter.emitted += 1,
+                Err(err) if self.collect_all_errors => errors.push((self.offset(segment), err)),
+                Err(err) => return Err(err),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(iter.emitted)
+        } else {
+            Err(HeaderParseError::Multiple {
+                errors,
+            })
+        }
+    }
+
+    /// Parse every cookie of a header using this parser's configuration, or reject the whole
+    /// header if any cookie is malformed.
+    ///
+    /// Unlike filtering the results of [`parse`](Self::parse), a single error, including a
+    /// configured limit being exceeded or a value failing to decode, fails the whole parse. The
+    /// error is returned as [`HeaderParseError::Multiple`] holding the first error and the byte
+    /// range of its segment, or every error if
+    /// [`collect_all_errors`](crate::HeaderStringParserBuilder::collect_all_errors) is enabled.
+    ///
+    /// What counts as malformed is decided by the configuration: segments that the parser skips
+    /// without an error, such as a `flag` without `=` in [`ParseMode::Lenient`], don't reject
+    /// the header. Use [`ParseMode::Strict`] or
+    /// [`error_on_empty_name`](crate::HeaderStringParserBuilder::error_on_empty_name) to make
+    /// them errors too.
+    pub fn parse_all_or_reject<'c, C, S>(&self, string: S) -> Result<Vec<C>, HeaderParseError>
+    where
+        C: CookieBuilder<'c>,
+        S: Into<Cow<'c, str>>,
+    {
+        let mut cookies = Vec::new();
+        let mut errors = Vec::new();
+
+        for (segment, result) in self.parse::<C, _>(string).indexed() {
+            match result {
+                Ok(cookie) if errors.is_empty() => cookies.push(cookie),
+                Ok(_) => {}
+                Err(err) => {
+                    errors.push((segment, err));
+                    if !self.collect_all_errors {
+                        break;
+                    }
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(cookies)
+        } else {
+            Err(HeaderParseError::Multiple {
+                errors,
+            })
+        }
+    }
+
+    /// Count the cookies a header contains using this parser's configuration, without
+    /// constructing any cookie.
+    ///
+    /// The header is split exactly as [`parse`](Self::parse) splits it, so the count matches the
+    /// number of cookies the iterator yields, except that cookies whose value would fail to
+    /// decode are counted too. Segments that yield an error are not counted.
+    pub fn count_cookies(&self, header: &str) -> usize {
+        // The cookie type is unused; the iterator only supplies ranges.
+        let mut iter = HeaderStringCookies::<Cookie>::new(Cow::Borrowed(header), self.clone());
+
+        while let Some((_, ranges)) = iter.next_ranges() {
+            if ranges.is_ok() {
+                iter.emitted += 1;
+            }
+        }
+
+        iter.emitted
+    }
+}
+
+/// Count the cookies a header contains using the default configuration, without constructing
+/// any cookie.
+///
+/// See [`HeaderStringParser::count_cookies`].
+///
+/// # Example
+///
+/// ```
+/// use ri_cookie_header_string::count_cookies;
+///
+/// assert_eq!(count_cookies("session=abc;123; theme=dark;; flag"), 2);
+/// ```
+pub fn count_cookies(header: &str) -> usize {
+    HeaderStringParser::new().count_cookies(header)
+}
+
+/// Parse every cookie of a header, or reject the whole header if any segment is not a
+/// well-formed cookie.
+///
+/// Uses [`ParseMode::Strict`] with
+/// [`error_on_empty_name`](crate::HeaderStringParserBuilder::error_on_empty_name), so the
+/// header is split at every `;` and a segment without `=` or with an empty name rejects it, as
+/// befits a high-security endpoint. See [`HeaderStringParser::parse_all_or_reject`] to choose
+/// the configuration, e.g. to keep the lenient heuristics.
+///
+/// # Example
+///
+/// ```
+/// use ri_cookie_header_string::{HeaderParseError, parse_all_or_reject};
+///
+/// let cookies = parse_all_or_reject("session=abc; theme=dark").unwrap();
+/// assert_eq!(cookies.len(), 2);
+///
+/// let Err(HeaderParseError::Multiple { errors }) = parse_all_or_reject("a=1; flag; b=2") else {
+///     panic!("the header should be rejected");
+/// };
+/// assert_eq!(errors.len(), 1);
+/// assert_eq!(errors[0].0, 4..9);
+/// ```
+pub fn parse_all_or_reject(header: &str) -> Result<Vec<Cookie<'_>>, HeaderParseError> {
+    HeaderStringParser::builder().mode(ParseMode::Strict).error_on_empty_name(true).build().parse_all_or_reject(header)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cookie::ParseError;
+
+    #[test]
+    fn count_cookies_matches_parse() {
+        let strict = HeaderStringParser::builder().mode(ParseMode::Strict).build();
+        let limited = HeaderStringParser::builder().max_cookies(2).build();
+
+        for header in ["", ";;", "a=1", "a=1; b=x;y; c", "a=\"q;r\"; =1; b=2; flag; c=3", " ; a=1;;b=2 ;"] {
+            for parser in [HeaderStringParser::new(), strict.clone(), limited.clone()] {
+                let parsed = parser.parse::<Cookie, _>(header).filter_map(|c| c.ok()).count();
+                assert_eq!(parser.count_cookies(header), parsed, "{header:?} with {parser:?}");
+            }
+        }
+        assert_eq!(count_cookies("a=1; b=2; c=3"), 3);
+    }
+
+    #[test]
+    fn validate_stops_at_first_error() {
+        let parser = HeaderStringParser::builder().mode(ParseMode::Strict).reject_control_chars(true).build();
+
+        assert_eq!(parser.validate(""), Ok(0));
+        assert_eq!(parser.validate("a=1; b=2;"), Ok(2));
+        assert_eq!(parser.validate("a=1; flag; b=\n"), Err(ParseError::MissingPair.into()));
+        assert_eq!(
+            parser.validate("a=1; b=\u{7}; flag"),
+            Err(HeaderParseError::ControlCharacter {
+                byte: 0x07
+            })
+        );
+
+        let lenient = HeaderStringParser::new();
+        assert_eq!(lenient.validate("a=1; flag; b=x;y"), Ok(2));
+    }
+
+    #[test]
+    fn validate_collects_all_errors() {
+        let parser = HeaderStringParser::builder()
+            .mode(ParseMode::Strict)
+            .reject_control_chars(true)
+            .collect_all_errors(true)
+            .base_offset(8)
+            .build();
+
+        assert_eq!(parser.validate("a=1; b=2"), Ok(2));
+        assert_eq!(
+            parser.validate("a=1; flag; b=\u{7}; c=3"),
+            Err(HeaderParseError::Multiple {
+                errors: vec![
+                    (12..17, ParseError::MissingPair.into()),
+                    (
+                        18..22,
+                        HeaderParseError::ControlCharacter {
+                            byte: 0x07
+                        }
+                    ),
+                ]
+            })
+        );
+
+        let limited = HeaderStringParser::builder().mode(ParseMode::Strict).max_cookies(1).collect_all_errors(true);
+        let Err(HeaderParseError::Multiple {
+            errors,
+        }) = limited.build().validate("flag; a=1; b=2; c")
+        else {
+            panic!("expected every error");
+        };
+        let errors: Vec<_> = errors.into_iter().map(|(_, err)| err).collect();
+        assert_eq!(
+            errors,
+            [
+                ParseError::MissingPair.into(),
+                HeaderParseError::TooManyCookies {
+                    limit: 1
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_all_or_reject_fails_on_any_bad_cookie() {
+        let cookies = parse_all_or_reject(" a=1; b=2;; c=\"q\" ").unwrap();
+        let pairs: Vec<_> = cookies.iter().map(Cookie::name_value).collect();
+        assert_eq!(pairs, [("a", "1"), ("b", "2"), ("c", "\"q\"")]);
+        assert_eq!(parse_all_or_reject(""), Ok(Vec::new()));
+
+        for (header, span, error) in [
+            ("a=1; b=2; flag", 9..14, ParseError::MissingPair),
+            ("=x; a=1", 0..2, ParseError::EmptyName),
+            ("a=1; b=x;y", 9..10, ParseError::MissingPair),
+        ] {
+            assert_eq!(
+                parse_all_or_reject(header),
+                Err(HeaderParseError::Multiple {
+                    errors: vec![(span, error.into())]
+                }),
+                "header: {header:?}"
+            );
+        }
+
+        // Limits reject the header too, and every error can be reported at once
+        let parser = HeaderStringParser::builder().max_value_len(3).collect_all_errors(true).build();
+        let Err(HeaderParseError::Multiple {
+            errors,
+        }) = parser.parse_all_or_reject::<Cookie, _>("a=1; b=long; c=3; d=longer")
+        else {
+            panic!("expected the header to be rejected");
+        };
+        let spans: Vec<_> = errors.iter().map(|(span, _)| span.clone()).collect();
+        assert_eq!(spans, [4..11, 17..26]);
+
+        // The lenient heuristics still apply with a lenient parser
+        assert_eq!(HeaderStringParser::new().parse_all_or_reject::<Cookie, _>("a=x;y; flag").map(|c| c.len()), Ok(1));
+    }
+}